@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{layer::Layer, service::Service, win_ctx::WinCtx};
+use crate::{dcel::smooth::SmoothMethod, layer::Layer, service::Service, win_ctx::WinCtx};
 use winit::{
     application::ApplicationHandler,
-    event::{KeyEvent, WindowEvent},
+    event::{ElementState, KeyEvent, WindowEvent},
     event_loop::ActiveEventLoop,
     keyboard::{Key, NamedKey},
     window::{Window, WindowId},
@@ -155,6 +155,55 @@ impl<'window> ApplicationHandler for App<'window> {
                 event_loop.exit();
             }
 
+            // 快捷键（不区分大小写）：
+            // T 四边形拆分为三角形，Q 三角形合并为四边形，R 加密，
+            // L / A / O 分别以 Laplacian、角度、优化方法光顺，K 简化，U 合并重合节点并清理退化面，
+            // I 删除孤岛，X 拆分连通分量，J 合并所有网格，
+            // P 查询鼠标位置，V 计算挖填方，B 增加剖面线顶点，N 提取断面，
+            // M 切换质量着色，C 显示或关闭等高线，D 切换数据集，[ / ] 前后切换时间步，
+            // G 增加断裂线顶点，H 结束当前断裂线（没有时清空断裂线），
+            // E 重新编号后导出，S 保存快照，F 切换拖入 .shp 时的裁剪方式
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Character(c),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(win_ctx) = self.window_id_context_map.get_mut(&window_id) {
+                    match c.as_str() {
+                        "t" | "T" => win_ctx.split_quads(),
+                        "q" | "Q" => win_ctx.merge_triangles(),
+                        "r" | "R" => win_ctx.refine(),
+                        "p" | "P" => win_ctx.probe(),
+                        "m" | "M" => win_ctx.cycle_quality_metric(),
+                        "l" | "L" => win_ctx.smooth(SmoothMethod::Laplacian),
+                        "a" | "A" => win_ctx.smooth(SmoothMethod::AngleBased),
+                        "o" | "O" => win_ctx.smooth(SmoothMethod::Optimized),
+                        "e" | "E" => win_ctx.export(),
+                        "j" | "J" => win_ctx.merge_coverages(),
+                        "c" | "C" => win_ctx.toggle_contours(),
+                        "v" | "V" => win_ctx.compute_volume(),
+                        "d" | "D" => win_ctx.cycle_dataset(),
+                        "[" => win_ctx.step_dataset(false),
+                        "]" => win_ctx.step_dataset(true),
+                        "i" | "I" => win_ctx.remove_islands(),
+                        "x" | "X" => win_ctx.split_components(),
+                        "s" | "S" => win_ctx.save_snapshots(),
+                        "k" | "K" => win_ctx.decimate(),
+                        "b" | "B" => win_ctx.add_section_point(),
+                        "n" | "N" => win_ctx.extract_profiles(),
+                        "u" | "U" => win_ctx.cleanup(),
+                        "f" | "F" => win_ctx.cycle_clip_options(),
+                        "g" | "G" => win_ctx.add_breakline_point(),
+                        "h" | "H" => win_ctx.finish_breakline(),
+                        _ => {}
+                    }
+                }
+            }
+
             WindowEvent::RedrawRequested => {
                 if let Some(win_ctx) = self.window_id_context_map.get_mut(&window_id) {
                     win_ctx.redraw();
@@ -1,37 +1,70 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Mutex,
-};
-
-static GLOBAL_NODE_ID_COUNTER: Mutex<u32> = Mutex::new(0);
-static GLOBAL_FACE_ID_COUNTER: Mutex<u32> = Mutex::new(0);
-static GLOBAL_HALF_EDGE_ID_COUNTER: Mutex<u32> = Mutex::new(0);
+pub mod boundary;
+pub mod cleanup;
+pub mod clip;
+pub mod component;
+pub mod contour;
+pub mod convert;
+pub mod dataset;
+pub mod decimate;
+pub mod delaunay;
+pub mod edit;
+pub mod geometry;
+pub mod locate;
+pub mod merge;
+pub mod profile;
+pub mod quality;
+pub mod refine;
+pub mod renumber;
+pub mod smooth;
+pub mod snapshot;
+pub mod spatial;
+pub mod transform;
+pub mod traverse;
+pub mod validate;
+pub mod volume;
+
+use std::collections::{HashMap, HashSet};
+
+use dataset::{Dataset, DatasetLocation};
+use serde::{Deserialize, Serialize};
+use snapshot::{serialize_map, serialize_set_map, MeshCoverageData};
+use spatial::{QuadTree, Rect};
 
 const MAX_ID: u32 = u32::MAX;
 
-pub fn generate_id(counter: &Mutex<u32>) -> u32 {
-    let current_val = counter.lock().unwrap().clone();
-    if current_val > MAX_ID {
-        panic!("Factory ids overflowed");
-    }
-    let next_id = current_val + 1;
-    *counter.lock().unwrap() = next_id;
-    next_id
+// 每个 MeshCoverage 独立的 id 分配器，0 保留表示“无”
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IdGenerator {
+    current: u32,
 }
 
-fn generate_node_id() -> u32 {
-    generate_id(&GLOBAL_NODE_ID_COUNTER)
-}
+impl IdGenerator {
+    pub fn new() -> Self {
+        Self { current: 0 }
+    }
 
-fn generate_face_id() -> u32 {
-    generate_id(&GLOBAL_FACE_ID_COUNTER)
-}
+    pub fn generate(&mut self) -> u32 {
+        if self.current == MAX_ID {
+            panic!("Factory ids overflowed");
+        }
+        self.current += 1;
+        self.current
+    }
+
+    // 使用外部指定的 id 后，保证之后生成的 id 不与之冲突
+    pub fn reserve(&mut self, id: u32) {
+        if id > self.current {
+            self.current = id;
+        }
+    }
 
-fn generate_half_edge_id() -> u32 {
-    generate_id(&GLOBAL_HALF_EDGE_ID_COUNTER)
+    pub fn current(&self) -> u32 {
+        self.current
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Node {
     pub x: f64,
     pub y: f64,
@@ -44,7 +77,7 @@ impl Node {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Face {
     pub n0: u32,
     pub n1: u32,
@@ -56,9 +89,22 @@ impl Face {
     fn new(n0: u32, n1: u32, n2: u32, n3: u32) -> Self {
         Self { n0, n1, n2, n3 }
     }
+
+    // n3 为 0 表示三角形
+    pub fn is_triangle(&self) -> bool {
+        self.n3 == 0
+    }
+
+    pub fn node_ids(&self) -> Vec<u32> {
+        if self.n3 > 0 {
+            vec![self.n0, self.n1, self.n2, self.n3]
+        } else {
+            vec![self.n0, self.n1, self.n2]
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct HalfEdge {
     pub start_id: u32,
     pub end_id: u32,
@@ -88,8 +134,10 @@ impl HalfEdge {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct NodeFaceAdj {
+    #[serde(serialize_with = "serialize_set_map")]
     map: HashMap<u32, HashSet<u32>>,
 }
 
@@ -125,8 +173,10 @@ impl NodeFaceAdj {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct FaceHalfEdgeAdj {
+    #[serde(serialize_with = "serialize_set_map")]
     map: HashMap<u32, HashSet<u32>>,
 }
 
@@ -206,14 +256,33 @@ impl BBox3 {
     }
 }
 
-#[derive(Debug, Clone)]
+// 序列化时不写出空间索引，反序列化后由节点与面重新生成；映射按 id 排序写出，便于比较
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "MeshCoverageData")]
 pub struct MeshCoverage {
     pub id: String,
+    #[serde(serialize_with = "serialize_map")]
     pub node_map: HashMap<u32, Node>,
+    #[serde(serialize_with = "serialize_map")]
     pub face_map: HashMap<u32, Face>,
+    #[serde(serialize_with = "serialize_map")]
     pub half_edge_map: HashMap<u32, HalfEdge>,
     pub node_face_adj: NodeFaceAdj,
     pub face_half_edge_adj: FaceHalfEdgeAdj,
+    pub node_id_generator: IdGenerator,
+    pub face_id_generator: IdGenerator,
+    pub half_edge_id_generator: IdGenerator,
+    // 节点与面包围盒的空间索引，由创建、删除及移动节点的方法维护
+    #[serde(skip)]
+    pub node_index: QuadTree,
+    #[serde(skip)]
+    pub face_index: QuadTree,
+    // 按名称存放的节点或面上的计算结果
+    #[serde(serialize_with = "serialize_map")]
+    pub dataset_map: HashMap<String, Dataset>,
+    // 简化时删除的原始节点 (x, y, z)，再次简化时一并检查误差，使偏差始终相对于原始表面；不写入快照
+    #[serde(skip)]
+    pub decimated_points: Vec<[f64; 3]>,
 }
 
 impl MeshCoverage {
@@ -225,6 +294,13 @@ impl MeshCoverage {
             half_edge_map: HashMap::new(),
             node_face_adj: NodeFaceAdj::new(),
             face_half_edge_adj: FaceHalfEdgeAdj::new(),
+            node_id_generator: IdGenerator::new(),
+            face_id_generator: IdGenerator::new(),
+            half_edge_id_generator: IdGenerator::new(),
+            node_index: QuadTree::new(),
+            face_index: QuadTree::new(),
+            dataset_map: HashMap::new(),
+            decimated_points: Vec::new(),
         }
     }
 
@@ -241,14 +317,50 @@ impl MeshCoverage {
     }
 
     pub fn create_node(&mut self, x: f64, y: f64, z: f64) -> u32 {
-        let new_id = generate_node_id();
+        let new_id = self.node_id_generator.generate();
         let new_node = Node::new(x, y, z);
         self.node_map.insert(new_id, new_node);
+        self.node_index.insert(new_id, Rect::point(x, y));
         new_id
     }
 
+    // 使用文件中的节点编号作为 id，面文件直接引用该编号
+    pub fn create_node_with_id(&mut self, id: u32, x: f64, y: f64, z: f64) -> u32 {
+        self.node_id_generator.reserve(id);
+        let new_node = Node::new(x, y, z);
+        self.node_map.insert(id, new_node);
+        self.node_index.insert(id, Rect::point(x, y));
+        id
+    }
+
+    // 移动节点并更新空间索引，修改坐标应使用该方法而不是直接修改 node_map
+    pub fn set_node_position(&mut self, id: u32, x: f64, y: f64, z: f64) {
+        let node = match self.node_map.get_mut(&id) {
+            Some(node) => node,
+            None => return,
+        };
+        node.x = x;
+        node.y = y;
+        node.z = z;
+        self.node_index.insert(id, Rect::point(x, y));
+        if let Some(set) = self.node_face_adj.get_node_adj_faces(id) {
+            let face_ids: Vec<u32> = set.iter().copied().collect();
+            for face_id in face_ids {
+                if let Some(rect) = self.get_face_rect(face_id) {
+                    self.face_index.insert(face_id, rect);
+                }
+            }
+        }
+    }
+
     pub fn create_face(&mut self, n0: u32, n1: u32, n2: u32, n3: u32) -> u32 {
-        let new_id = generate_face_id();
+        let new_id = self.face_id_generator.generate();
+        self.create_face_with_id(new_id, n0, n1, n2, n3)
+    }
+
+    // 使用指定的 id 创建面，如重新编号时保留原有的面编号
+    pub fn create_face_with_id(&mut self, new_id: u32, n0: u32, n1: u32, n2: u32, n3: u32) -> u32 {
+        self.face_id_generator.reserve(new_id);
 
         // 绑定点
         self.node_face_adj.bind(n0, new_id);
@@ -260,28 +372,135 @@ impl MeshCoverage {
 
         let new_face = Face::new(n0, n1, n2, n3);
         self.face_map.insert(new_id, new_face);
+        if let Some(rect) = self.get_face_rect(new_id) {
+            self.face_index.insert(new_id, rect);
+        }
         new_id
     }
 
     pub fn generate_half_edges(&mut self) {
-        for (id, face) in &mut self.face_map {
-            let n0 = face.n0.clone();
-            let n1 = face.n1.clone();
-            let n2 = face.n2.clone();
-            let n3 = face.n3.clone();
-            let id = id.clone();
+        // 清空旧的半边，重新构建
+        self.half_edge_map.clear();
+        self.face_half_edge_adj = FaceHalfEdgeAdj::new();
+        self.half_edge_id_generator = IdGenerator::new();
+
+        let mut faces: Vec<(u32, Face)> = self
+            .face_map
+            .iter()
+            .map(|(&id, &face)| (id, face))
+            .collect();
+        faces.sort_by_key(|(id, _)| *id);
+        for (id, face) in faces {
             // 创建半边
-            // self.create_face_half_edges(n0, n1, n2, n3, id);
+            self.create_face_half_edges(face.n0, face.n1, face.n2, face.n3, id);
+        }
+
+        // 创建边界半边
+        self.generate_boundary_half_edges();
+    }
+
+    // 为没有对边的半边创建边界半边（face_id 为 0），并按边界环连接 prev/next
+    pub fn generate_boundary_half_edges(&mut self) {
+        let mut inner_ids: Vec<u32> = self
+            .half_edge_map
+            .iter()
+            .filter(|(_, half_edge)| half_edge.face_id > 0 && half_edge.twin_id == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        inner_ids.sort();
+
+        let mut boundary_ids: Vec<u32> = Vec::with_capacity(inner_ids.len());
+        for inner_id in inner_ids {
+            let inner = self.half_edge_map[&inner_id];
+            let id = self.half_edge_id_generator.generate();
+            let half_edge = HalfEdge::new(inner.end_id, inner.start_id, 0, 0, 0, inner_id);
+            self.half_edge_map.insert(id, half_edge);
+            self.half_edge_map.get_mut(&inner_id).unwrap().twin_id = id;
+            boundary_ids.push(id);
+        }
+
+        for id in boundary_ids {
+            self.link_boundary_half_edge(id);
+        }
+    }
+
+    // 绕终点旋转，找到同一扇区内的下一条边界半边并连接 next/prev
+    fn link_boundary_half_edge(&mut self, id: u32) {
+        let end_id = self.half_edge_map[&id].end_id;
+        let mut current = self.half_edge_map[&id].twin_id;
+        let mut visited: HashSet<u32> = HashSet::new();
+        let next_id = loop {
+            let prev = self.half_edge_map[&current].prev_id;
+            let twin = self.half_edge_map[&prev].twin_id;
+            match self.half_edge_map.get(&twin) {
+                Some(half_edge) if half_edge.face_id == 0 => break twin,
+                // 非流形时旋转可能无法闭合，退回到任意一条从终点出发的边界半边
+                Some(_) if visited.insert(twin) => current = twin,
+                _ => break self.find_boundary_half_edge_from(end_id).unwrap_or(id),
+            }
+        };
+        self.half_edge_map.get_mut(&id).unwrap().next_id = next_id;
+        self.half_edge_map.get_mut(&next_id).unwrap().prev_id = id;
+    }
+
+    // 从节点出发的任意一条边界半边
+    pub fn find_boundary_half_edge_from(&self, node_id: u32) -> Option<u32> {
+        for half_edge_id in self.get_node_face_half_edges(node_id) {
+            let half_edge = self.half_edge_map[&half_edge_id];
+            if half_edge.end_id == node_id && self.is_boundary_half_edge(half_edge.twin_id) {
+                return Some(half_edge.twin_id);
+            }
+        }
+        None
+    }
+
+    // 相邻面上经过该节点的面内半边
+    fn get_node_face_half_edges(&self, node_id: u32) -> Vec<u32> {
+        let mut result = Vec::new();
+        if let Some(face_set) = self.node_face_adj.get_node_adj_faces(node_id) {
+            for face_id in face_set {
+                if let Some(half_edge_set) =
+                    self.face_half_edge_adj.get_face_adj_half_edges(*face_id)
+                {
+                    for &id in half_edge_set {
+                        let half_edge = self.half_edge_map[&id];
+                        if half_edge.start_id == node_id || half_edge.end_id == node_id {
+                            result.push(id);
+                        }
+                    }
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    // 重新连接经过这些节点的边界半边
+    fn relink_boundary_half_edges(&mut self, node_ids: &[u32]) {
+        for &node_id in node_ids {
+            for half_edge_id in self.get_node_face_half_edges(node_id) {
+                let half_edge = self.half_edge_map[&half_edge_id];
+                if half_edge.start_id == node_id && self.is_boundary_half_edge(half_edge.twin_id) {
+                    self.link_boundary_half_edge(half_edge.twin_id);
+                }
+            }
+        }
+    }
+
+    pub fn is_boundary_half_edge(&self, id: u32) -> bool {
+        match self.half_edge_map.get(&id) {
+            Some(half_edge) => half_edge.face_id == 0,
+            None => false,
         }
     }
 
     pub fn create_face_half_edges(&mut self, n0: u32, n1: u32, n2: u32, n3: u32, face_id: u32) {
-        let half_edge_0_id = generate_half_edge_id();
-        let half_edge_1_id = generate_half_edge_id();
-        let half_edge_2_id = generate_half_edge_id();
+        let half_edge_0_id = self.half_edge_id_generator.generate();
+        let half_edge_1_id = self.half_edge_id_generator.generate();
+        let half_edge_2_id = self.half_edge_id_generator.generate();
         let mut last_half_edge_id = half_edge_2_id;
         if n3 > 0 {
-            let half_edge_3_id = generate_half_edge_id();
+            let half_edge_3_id = self.half_edge_id_generator.generate();
             last_half_edge_id = half_edge_3_id;
             self.create_half_edge(
                 half_edge_3_id,
@@ -338,18 +557,21 @@ impl MeshCoverage {
         prev_id: u32,
         next_id: u32,
     ) {
+        let twin_id = self.find_twin(start_id, end_id, face_id);
         self.face_half_edge_adj.bind(face_id, id);
-        let half_edge = HalfEdge::new(
-            start_id,
-            end_id,
-            face_id,
-            prev_id,
-            next_id,
-            self.find_twin(start_id, end_id, face_id),
-        );
+        let half_edge = HalfEdge::new(start_id, end_id, face_id, prev_id, next_id, twin_id);
         self.half_edge_map.insert(id, half_edge);
+        // 对边同时指向新建的半边，原来的边界半边不再需要；find_twin 只返回尚未与面内半边配对的对边
+        if let Some(twin) = self.half_edge_map.get_mut(&twin_id) {
+            let old_twin_id = twin.twin_id;
+            twin.twin_id = id;
+            if self.is_boundary_half_edge(old_twin_id) {
+                self.half_edge_map.remove(&old_twin_id);
+            }
+        }
     }
 
+    // 反向的面内半边，已与其他面内半边配对的跳过：非流形边上多出的面不配对，由边界半边补齐
     pub fn find_twin(&mut self, start_id: u32, end_id: u32, face_id: u32) -> u32 {
         if let Some(face_set) = self.node_face_adj.get_node_adj_faces(start_id) {
             for &id in face_set {
@@ -358,7 +580,13 @@ impl MeshCoverage {
                     {
                         for &id2 in half_edge_set {
                             if let Some(half_edge) = self.half_edge_map.get(&id2) {
-                                if half_edge.start_id == end_id && half_edge.end_id == start_id {
+                                if half_edge.start_id == end_id
+                                    && half_edge.end_id == start_id
+                                    && self
+                                        .half_edge_map
+                                        .get(&half_edge.twin_id)
+                                        .is_none_or(|twin| twin.face_id == 0)
+                                {
                                     return id2;
                                 }
                             }
@@ -370,31 +598,85 @@ impl MeshCoverage {
         0
     }
 
+    // 创建面并同步半边拓扑，需已生成半边
+    pub fn add_face(&mut self, n0: u32, n1: u32, n2: u32, n3: u32) -> u32 {
+        let face_id = self.create_face(n0, n1, n2, n3);
+        self.create_face_half_edges(n0, n1, n2, n3, face_id);
+
+        // 新面上没有对边的半边需要边界半边
+        let mut half_edge_ids: Vec<u32> = self
+            .face_half_edge_adj
+            .get_face_adj_half_edges(face_id)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect();
+        half_edge_ids.sort();
+        for inner_id in half_edge_ids {
+            let inner = self.half_edge_map[&inner_id];
+            if inner.twin_id == 0 {
+                let id = self.half_edge_id_generator.generate();
+                let half_edge = HalfEdge::new(inner.end_id, inner.start_id, 0, 0, 0, inner_id);
+                self.half_edge_map.insert(id, half_edge);
+                self.half_edge_map.get_mut(&inner_id).unwrap().twin_id = id;
+            }
+        }
+
+        self.relink_boundary_half_edges(&self.face_map[&face_id].node_ids());
+        face_id
+    }
+
     pub fn remove_node(&mut self, node_id: u32) {
-        self.node_map.remove(&node_id);
-        // 删除关联的三角形及半边
+        // 删除关联的面及半边，并解绑相邻节点
         if let Some(set) = self.node_face_adj.get_node_adj_faces(node_id) {
-            for &id in set {
-                self.face_map.remove(&id);
-                self.face_half_edge_adj.remove_face(id);
+            let mut face_ids: Vec<u32> = set.iter().copied().collect();
+            face_ids.sort();
+            for id in face_ids {
+                self.remove_face(id);
             }
         }
+        self.node_map.remove(&node_id);
         self.node_face_adj.remove_node(node_id);
+        self.node_index.remove(node_id);
+        self.remove_dataset_values(DatasetLocation::Node, node_id);
     }
 
     pub fn remove_face(&mut self, face_id: u32) {
+        let face = match self.face_map.remove(&face_id) {
+            Some(face) => face,
+            None => return,
+        };
+        self.face_index.remove(face_id);
+        self.remove_dataset_values(DatasetLocation::Face, face_id);
         // 解绑点
-        if let Some(face) = self.face_map.get(&face_id) {
-            self.node_face_adj.unbind(face.n0, face_id);
-            self.node_face_adj.unbind(face.n1, face_id);
-            self.node_face_adj.unbind(face.n2, face_id);
-            if face.n3 > 0 {
-                self.node_face_adj.unbind(face.n3, face_id)
-            };
+        self.node_face_adj.unbind(face.n0, face_id);
+        self.node_face_adj.unbind(face.n1, face_id);
+        self.node_face_adj.unbind(face.n2, face_id);
+        if face.n3 > 0 {
+            self.node_face_adj.unbind(face.n3, face_id)
+        };
+
+        // 删除半边：对边为边界半边时一并删除，否则由新的边界半边代替
+        if let Some(set) = self.face_half_edge_adj.get_face_adj_half_edges(face_id) {
+            let mut half_edge_ids: Vec<u32> = set.iter().copied().collect();
+            half_edge_ids.sort();
+            for id in half_edge_ids {
+                let half_edge = self.half_edge_map.remove(&id).unwrap();
+                let twin_id = half_edge.twin_id;
+                if self.is_boundary_half_edge(twin_id) {
+                    self.half_edge_map.remove(&twin_id);
+                } else if self.half_edge_map.contains_key(&twin_id) {
+                    let boundary =
+                        HalfEdge::new(half_edge.start_id, half_edge.end_id, 0, 0, 0, twin_id);
+                    let boundary_id = self.half_edge_id_generator.generate();
+                    self.half_edge_map.insert(boundary_id, boundary);
+                    self.half_edge_map.get_mut(&twin_id).unwrap().twin_id = boundary_id;
+                }
+            }
         }
-        // 删除半边
-        self.face_map.remove(&face_id);
         self.face_half_edge_adj.remove_face(face_id);
+
+        self.relink_boundary_half_edges(&face.node_ids());
     }
 
     pub fn generate_buffer(&self) -> (Vec<f32>, Vec<u32>) {
@@ -478,3 +760,45 @@ impl MeshCoverage {
         bbox3
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每条半边的对边都指向自己，next/prev 互相对应
+    fn assert_links(coverage: &MeshCoverage) {
+        for (&id, half_edge) in &coverage.half_edge_map {
+            let twin = coverage.half_edge_map[&half_edge.twin_id];
+            assert_eq!(twin.twin_id, id);
+            assert_eq!(
+                (twin.start_id, twin.end_id),
+                (half_edge.end_id, half_edge.start_id)
+            );
+            assert_eq!(coverage.half_edge_map[&half_edge.next_id].prev_id, id);
+            assert_eq!(coverage.half_edge_map[&half_edge.prev_id].next_id, id);
+        }
+    }
+
+    #[test]
+    fn non_manifold_edge_keeps_twins() {
+        // 三个三角形共用边 a-b
+        let mut coverage = MeshCoverage::new("a".into());
+        let a = coverage.create_node(0.0, 0.0, 0.0);
+        let b = coverage.create_node(1.0, 0.0, 0.0);
+        let c = coverage.create_node(0.5, 1.0, 0.0);
+        let d = coverage.create_node(0.5, -1.0, 0.0);
+        let e = coverage.create_node(0.7, -2.0, 0.0);
+        coverage.create_face(a, b, c, 0);
+        coverage.create_face(b, a, d, 0);
+        coverage.generate_half_edges();
+        assert_links(&coverage);
+
+        coverage.add_face(b, a, e, 0);
+        assert_links(&coverage);
+        let mut fresh = coverage.clone();
+        fresh.generate_half_edges();
+        assert_links(&fresh);
+        assert_eq!(fresh.half_edge_map.len(), coverage.half_edge_map.len());
+        assert_eq!(coverage.validate().non_manifold_edges.len(), 1);
+    }
+}
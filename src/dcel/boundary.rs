@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use super::MeshCoverage;
+
+#[derive(Debug, Clone, Default)]
+pub struct MeshBoundary {
+    // 外边界，逆时针，首尾节点不重复
+    pub outer_loops: Vec<Vec<u32>>,
+    // 内部孔洞，顺时针，首尾节点不重复
+    pub holes: Vec<Vec<u32>>,
+}
+
+impl MeshBoundary {
+    pub fn loops(&self) -> impl Iterator<Item = &Vec<u32>> {
+        self.outer_loops.iter().chain(self.holes.iter())
+    }
+}
+
+impl MeshCoverage {
+    // 沿边界半边（没有对边的边）提取外边界与孔洞，需先调用 generate_half_edges
+    pub fn get_boundary(&self) -> MeshBoundary {
+        let mut boundary = MeshBoundary::default();
+
+        let mut start_ids: Vec<u32> = self
+            .half_edge_map
+            .iter()
+            .filter(|(_, half_edge)| half_edge.face_id == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        start_ids.sort();
+
+        let winding = self.get_face_winding();
+        let mut visited: HashSet<u32> = HashSet::new();
+        for start_id in start_ids {
+            if visited.contains(&start_id) {
+                continue;
+            }
+            // 边界半边的方向与面内侧相反，反转后得到与面一致的方向
+            let mut nodes: Vec<u32> = Vec::new();
+            for id in self.iter_boundary_loop(start_id) {
+                if !visited.insert(id) {
+                    break;
+                }
+                nodes.push(self.half_edge_map[&id].start_id);
+            }
+            nodes.reverse();
+
+            let area = self.get_polygon_signed_area(&nodes);
+            if area * winding > 0.0 {
+                if area < 0.0 {
+                    nodes.reverse();
+                }
+                boundary.outer_loops.push(nodes);
+            } else {
+                if area > 0.0 {
+                    nodes.reverse();
+                }
+                boundary.holes.push(nodes);
+            }
+        }
+
+        boundary
+    }
+
+    pub fn get_polygon_signed_area(&self, node_ids: &[u32]) -> f64 {
+        let mut area = 0.0;
+        let len = node_ids.len();
+        for i in 0..len {
+            if let (Some(p), Some(q)) = (
+                self.node_map.get(&node_ids[i]),
+                self.node_map.get(&node_ids[(i + 1) % len]),
+            ) {
+                area += p.x * q.y - q.x * p.y;
+            }
+        }
+        area / 2.0
+    }
+
+    // 面的主要绕向：逆时针为 1，顺时针为 -1
+    pub fn get_face_winding(&self) -> f64 {
+        let mut sum = 0.0;
+        for face in self.face_map.values() {
+            sum += self.get_polygon_signed_area(&face.node_ids()).signum();
+        }
+        if sum < 0.0 {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+}
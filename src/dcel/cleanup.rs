@@ -0,0 +1,266 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use super::{
+    geometry::{is_zero_area, signed_area},
+    spatial::Rect,
+    Face, FaceHalfEdgeAdj, MeshCoverage,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CleanupChange {
+    // remove_id 合并到 keep_id，保留 keep_id 的坐标
+    MergeNode {
+        keep_id: u32,
+        remove_id: u32,
+    },
+    // 面的节点改为合并后的节点，四边形可能变为三角形
+    RemapFace {
+        face_id: u32,
+        old_node_ids: Vec<u32>,
+        node_ids: Vec<u32>,
+    },
+    // 引用了不存在的节点，或合并后节点重复、面积为零的面
+    DropDegenerateFace {
+        face_id: u32,
+        node_ids: Vec<u32>,
+    },
+    // 合并后与 id 较小的面节点完全相同的面
+    DropDuplicateFace {
+        face_id: u32,
+        duplicate_of: u32,
+    },
+    // 面积小于阈值的面
+    DropSliverFace {
+        face_id: u32,
+        area: f64,
+    },
+    // 因删除面而不再属于任何面的节点
+    RemoveOrphanNode {
+        node_id: u32,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CleanupReport {
+    // 按执行顺序排列
+    pub changes: Vec<CleanupChange>,
+}
+
+impl CleanupReport {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn count<F: Fn(&CleanupChange) -> bool>(&self, f: F) -> usize {
+        self.changes.iter().filter(|change| f(change)).count()
+    }
+}
+
+impl fmt::Display for CleanupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "cleanup:\n  merged nodes: {}",
+            self.count(|c| matches!(c, CleanupChange::MergeNode { .. }))
+        )?;
+        writeln!(
+            f,
+            "  remapped faces: {}",
+            self.count(|c| matches!(c, CleanupChange::RemapFace { .. }))
+        )?;
+        writeln!(
+            f,
+            "  degenerate faces: {}",
+            self.count(|c| matches!(c, CleanupChange::DropDegenerateFace { .. }))
+        )?;
+        writeln!(
+            f,
+            "  duplicate faces: {}",
+            self.count(|c| matches!(c, CleanupChange::DropDuplicateFace { .. }))
+        )?;
+        writeln!(
+            f,
+            "  sliver faces: {}",
+            self.count(|c| matches!(c, CleanupChange::DropSliverFace { .. }))
+        )?;
+        write!(
+            f,
+            "  orphan nodes removed: {}",
+            self.count(|c| matches!(c, CleanupChange::RemoveOrphanNode { .. }))
+        )
+    }
+}
+
+impl MeshCoverage {
+    // 合并平面距离不超过 tolerance 的节点，改写引用它们的面，删除因此退化或重复的面，
+    // 再删除面积小于 min_area 的面及因此孤立的节点，最后重建半边拓扑
+    pub fn cleanup(&mut self, tolerance: f64, min_area: f64) -> CleanupReport {
+        let mut report = CleanupReport::default();
+        let node_ids = self.get_merged_node_ids(tolerance);
+        let mut keep_ids: Vec<(u32, u32)> = node_ids.iter().map(|(&r, &k)| (k, r)).collect();
+        keep_ids.sort();
+        for (keep_id, remove_id) in keep_ids {
+            report
+                .changes
+                .push(CleanupChange::MergeNode { keep_id, remove_id });
+        }
+
+        // 半边最后统一重建，删除面时不需要维护
+        self.half_edge_map.clear();
+        self.face_half_edge_adj = FaceHalfEdgeAdj::new();
+
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        let mut touched_nodes: HashSet<u32> = HashSet::new();
+        let mut drop_ids: Vec<u32> = Vec::new();
+        let mut node_sets: HashMap<Vec<u32>, u32> = HashMap::new();
+        for face_id in face_ids {
+            let old_node_ids = self.face_map[&face_id].node_ids();
+            if !old_node_ids.iter().all(|id| self.node_map.contains_key(id)) {
+                report.changes.push(CleanupChange::DropDegenerateFace {
+                    face_id,
+                    node_ids: old_node_ids.clone(),
+                });
+                touched_nodes.extend(old_node_ids);
+                drop_ids.push(face_id);
+                continue;
+            }
+            let mut ids: Vec<u32> = Vec::new();
+            for id in &old_node_ids {
+                let id = node_ids.get(id).copied().unwrap_or(*id);
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+            if ids.len() > 1 && ids.first() == ids.last() {
+                ids.pop();
+            }
+
+            let unique: HashSet<u32> = ids.iter().copied().collect();
+            if unique.len() < 3
+                || unique.len() < ids.len()
+                || is_zero_area(&self.get_face_points(&ids))
+            {
+                report.changes.push(CleanupChange::DropDegenerateFace {
+                    face_id,
+                    node_ids: ids,
+                });
+                touched_nodes.extend(old_node_ids);
+                drop_ids.push(face_id);
+                continue;
+            }
+            let mut key = ids.clone();
+            key.sort();
+            if let Some(&duplicate_of) = node_sets.get(&key) {
+                report.changes.push(CleanupChange::DropDuplicateFace {
+                    face_id,
+                    duplicate_of,
+                });
+                touched_nodes.extend(old_node_ids);
+                drop_ids.push(face_id);
+                continue;
+            }
+            node_sets.insert(key, face_id);
+
+            if ids != old_node_ids {
+                for &id in &old_node_ids {
+                    self.node_face_adj.unbind(id, face_id);
+                }
+                for &id in &ids {
+                    self.node_face_adj.bind(id, face_id);
+                }
+                self.face_map.insert(
+                    face_id,
+                    Face::new(ids[0], ids[1], ids[2], ids.get(3).copied().unwrap_or(0)),
+                );
+                report.changes.push(CleanupChange::RemapFace {
+                    face_id,
+                    old_node_ids,
+                    node_ids: ids,
+                });
+            }
+        }
+        for &face_id in &drop_ids {
+            self.remove_face(face_id);
+        }
+
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            let ids = self.face_map[&face_id].node_ids();
+            let area = signed_area(&self.get_face_points(&ids)).abs();
+            if area < min_area {
+                report
+                    .changes
+                    .push(CleanupChange::DropSliverFace { face_id, area });
+                self.remove_face(face_id);
+                touched_nodes.extend(ids);
+            }
+        }
+
+        let mut remove_ids: Vec<u32> = node_ids.keys().copied().collect();
+        remove_ids.sort();
+        for node_id in remove_ids {
+            self.remove_node(node_id);
+        }
+        let mut orphan_ids: Vec<u32> = touched_nodes
+            .into_iter()
+            .filter(|id| {
+                self.node_map.contains_key(id)
+                    && self
+                        .node_face_adj
+                        .get_node_adj_faces(*id)
+                        .is_none_or(|set| set.is_empty())
+            })
+            .collect();
+        orphan_ids.sort();
+        for node_id in orphan_ids {
+            self.remove_node(node_id);
+            report
+                .changes
+                .push(CleanupChange::RemoveOrphanNode { node_id });
+        }
+
+        self.generate_half_edges();
+        self.rebuild_index();
+        report
+    }
+
+    // 被合并的节点 -> 保留的节点：按 id 从小到大，未被合并的节点吸收 tolerance 范围内其余未被合并的节点，
+    // 只与保留节点比较距离，避免沿一串相近节点连续合并
+    fn get_merged_node_ids(&self, tolerance: f64) -> HashMap<u32, u32> {
+        let mut result: HashMap<u32, u32> = HashMap::new();
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return result;
+        }
+        let mut node_ids: Vec<u32> = self.node_map.keys().copied().collect();
+        node_ids.sort();
+        for keep_id in node_ids {
+            if result.contains_key(&keep_id) {
+                continue;
+            }
+            let node = self.node_map[&keep_id];
+            let rect = Rect::new(
+                node.x - tolerance,
+                node.y - tolerance,
+                node.x + tolerance,
+                node.y + tolerance,
+            );
+            let mut near: Vec<u32> = self.node_index.query_rect(&rect);
+            near.sort();
+            for id in near {
+                if id <= keep_id || result.contains_key(&id) {
+                    continue;
+                }
+                let other = self.node_map[&id];
+                if (other.x - node.x).hypot(other.y - node.y) <= tolerance {
+                    result.insert(id, keep_id);
+                }
+            }
+        }
+        result
+    }
+}
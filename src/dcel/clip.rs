@@ -0,0 +1,369 @@
+use std::collections::{HashMap, HashSet};
+
+use geo_booleanop::boolean::BooleanOp;
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
+
+use super::{
+    delaunay::TriangulateOptions,
+    geometry::{point_in_polygon, point_segment_distance, project_to_segment, signed_area},
+    spatial::{QuadTree, Rect},
+    MeshCoverage,
+};
+
+// 面积比例小于该值的部分视为数值误差
+const AREA_EPSILON: f64 = 1e-9;
+
+type Ring = Vec<(f64, f64)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipKeep {
+    // 保留多边形内的部分
+    Inside,
+    // 删除多边形内的部分
+    Outside,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipCrossing {
+    // 沿多边形边界切开并重新剖分为三角形
+    Split,
+    // 按形心是否在多边形内整体保留或删除
+    Centroid,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClipOptions {
+    pub keep: ClipKeep,
+    pub crossing: ClipCrossing,
+}
+
+impl Default for ClipOptions {
+    fn default() -> Self {
+        Self {
+            keep: ClipKeep::Inside,
+            crossing: ClipCrossing::Split,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClipReport {
+    // 整体删除的面
+    pub removed_faces: Vec<u32>,
+    // 被切开的面，已删除并由 created_faces 代替
+    pub split_faces: Vec<u32>,
+    pub created_faces: Vec<u32>,
+    // 不再属于任何面而被删除的节点
+    pub removed_nodes: Vec<u32>,
+    // 引用了不存在的节点而未处理的面，保持不变
+    pub skipped_faces: Vec<u32>,
+}
+
+// 多边形环的坐标，去掉闭合点及连续重复点
+pub fn ring_points(ring: &LineString<f64>) -> Vec<(f64, f64)> {
+    let mut points: Vec<(f64, f64)> = Vec::with_capacity(ring.0.len());
+    for c in &ring.0 {
+        if points.last() != Some(&(c.x, c.y)) {
+            points.push((c.x, c.y));
+        }
+    }
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    points
+}
+
+fn polygon_area(polygon: &Polygon<f64>) -> f64 {
+    signed_area(&ring_points(polygon.exterior())).abs()
+        - polygon
+            .interiors()
+            .iter()
+            .map(|ring| signed_area(&ring_points(ring)).abs())
+            .sum::<f64>()
+}
+
+pub fn point_in_multi_polygon(point: (f64, f64), polygons: &MultiPolygon<f64>) -> bool {
+    polygons.0.iter().any(|polygon| {
+        point_in_polygon(point, &ring_points(polygon.exterior()))
+            && !polygon
+                .interiors()
+                .iter()
+                .any(|ring| point_in_polygon(point, &ring_points(ring)))
+    })
+}
+
+impl MeshCoverage {
+    // 用多边形（可含孔洞，如 shapefile 中的面）裁剪网格，跨越多边形边界的面按 options.crossing 处理；
+    // 含有孔洞的切割结果无法直接剖分，退回按形心处理
+    pub fn clip(&mut self, polygons: &MultiPolygon<f64>, options: &ClipOptions) -> ClipReport {
+        let mut report = ClipReport::default();
+
+        // 多边形各边的空间索引，包围盒不与任何边相交的面整体在多边形内或外
+        let mut edges = QuadTree::new();
+        let mut count = 0;
+        for polygon in &polygons.0 {
+            for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+                let points = ring_points(ring);
+                for i in 0..points.len() {
+                    if let Some(rect) =
+                        Rect::from_points(&[points[i], points[(i + 1) % points.len()]])
+                    {
+                        count += 1;
+                        edges.insert(count, rect);
+                    }
+                }
+            }
+        }
+
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        // 被切开的面及保留部分的外环
+        let mut pieces: Vec<(u32, Vec<Ring>)> = Vec::new();
+        for face_id in face_ids {
+            let ids = self.face_map[&face_id].node_ids();
+            if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                report.skipped_faces.push(face_id);
+                continue;
+            }
+            let points = self.get_face_points(&ids);
+            let centroid_inside = {
+                let len = points.len() as f64;
+                let x = points.iter().map(|p| p.0).sum::<f64>() / len;
+                let y = points.iter().map(|p| p.1).sum::<f64>() / len;
+                point_in_multi_polygon((x, y), polygons)
+            };
+            let keep_centroid = centroid_inside == (options.keep == ClipKeep::Inside);
+            let rect = match Rect::from_points(&points) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            if edges.query_rect(&rect).is_empty() {
+                if !keep_centroid {
+                    report.removed_faces.push(face_id);
+                }
+                continue;
+            }
+
+            let mut ring: Vec<Coordinate<f64>> =
+                points.iter().map(|&(x, y)| Coordinate { x, y }).collect();
+            ring.push(ring[0]);
+            let face_polygon = Polygon::new(LineString(ring), vec![]);
+            let face_area = signed_area(&points).abs();
+            let kept = match options.keep {
+                ClipKeep::Inside => face_polygon.intersection(polygons),
+                ClipKeep::Outside => face_polygon.difference(polygons),
+            };
+            let kept: Vec<&Polygon<f64>> = kept
+                .0
+                .iter()
+                .filter(|polygon| polygon_area(polygon) > face_area * AREA_EPSILON)
+                .collect();
+            let kept_area: f64 = kept.iter().map(|polygon| polygon_area(polygon)).sum();
+
+            if kept_area <= face_area * AREA_EPSILON {
+                report.removed_faces.push(face_id);
+            } else if kept_area >= face_area * (1.0 - AREA_EPSILON) {
+                continue;
+            } else if options.crossing == ClipCrossing::Centroid
+                || kept.iter().any(|polygon| !polygon.interiors().is_empty())
+            {
+                if !keep_centroid {
+                    report.removed_faces.push(face_id);
+                }
+            } else {
+                let rings = kept
+                    .iter()
+                    .map(|polygon| ring_points(polygon.exterior()))
+                    .filter(|ring| ring.len() >= 3)
+                    .collect();
+                pieces.push((face_id, rings));
+            }
+        }
+
+        // 先在原来的面上插值新节点的高程，再删除面
+        let first_new_id = self.node_id_generator.current();
+        // 新面及其所在的原面
+        let mut new_faces: Vec<(u32, [u32; 3])> = Vec::new();
+        let mut candidates: HashSet<u32> = HashSet::new();
+        for (face_id, rings) in pieces {
+            let sign = signed_area(&self.get_face_points(&self.face_map[&face_id].node_ids()));
+            for ring in rings {
+                let ids: Vec<u32> = ring
+                    .iter()
+                    .map(|&(x, y)| self.get_or_create_clip_node(face_id, x, y))
+                    .collect();
+                candidates.extend(ids.iter().copied());
+                let faces = self.triangulate_ring_nodes(&ids, sign);
+                new_faces.extend(faces.into_iter().map(|face| (face_id, face)));
+            }
+            report.split_faces.push(face_id);
+        }
+
+        // 新节点落在未切割的面的边上时（如多边形顶点恰好在对角线上），该面也在新节点处拆分，避免悬挂节点
+        let split: HashSet<u32> = report
+            .removed_faces
+            .iter()
+            .chain(&report.split_faces)
+            .chain(&report.skipped_faces)
+            .copied()
+            .collect();
+        let mut new_ids: Vec<u32> = candidates
+            .iter()
+            .copied()
+            .filter(|&id| id > first_new_id)
+            .collect();
+        new_ids.sort();
+        let mut inserted: HashMap<u32, Vec<(usize, f64, u32)>> = HashMap::new();
+        for node_id in new_ids {
+            let node = self.node_map[&node_id];
+            let p = (node.x, node.y);
+            for face_id in self.face_index.query_rect(&Rect::point(p.0, p.1)) {
+                if split.contains(&face_id) {
+                    continue;
+                }
+                let ids = self.face_map[&face_id].node_ids();
+                let points = self.get_face_points(&ids);
+                let rect = Rect::from_points(&points).unwrap();
+                let tolerance =
+                    (rect.max_x - rect.min_x).max(rect.max_y - rect.min_y) * AREA_EPSILON;
+                let len = ids.len();
+                for i in 0..len {
+                    let (a, b) = (points[i], points[(i + 1) % len]);
+                    let t = project_to_segment(p, a, b);
+                    if t > 0.0 && t < 1.0 && point_segment_distance(p, a, b) <= tolerance {
+                        inserted.entry(face_id).or_default().push((i, t, node_id));
+                    }
+                }
+            }
+        }
+        let mut face_ids: Vec<u32> = inserted.keys().copied().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            let mut nodes = inserted.remove(&face_id).unwrap();
+            nodes.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+            let face_nodes = self.face_map[&face_id].node_ids();
+            let mut ids: Vec<u32> = Vec::new();
+            for (i, id) in face_nodes.iter().enumerate() {
+                ids.push(*id);
+                ids.extend(nodes.iter().filter(|n| n.0 == i).map(|n| n.2));
+            }
+            let sign = signed_area(&self.get_face_points(&face_nodes));
+            let faces = self.triangulate_ring_nodes(&ids, sign);
+            new_faces.extend(faces.into_iter().map(|face| (face_id, face)));
+            report.split_faces.push(face_id);
+        }
+
+        for face_id in report.removed_faces.iter().chain(&report.split_faces) {
+            candidates.extend(self.face_map[face_id].node_ids());
+        }
+        let values = self.get_face_values(&report.split_faces);
+        for face_id in report.removed_faces.iter().chain(&report.split_faces) {
+            self.remove_face(*face_id);
+        }
+        for (face_id, [a, b, c]) in new_faces {
+            let new_id = self.add_face(a, b, c, 0);
+            self.interpolate_face_values(&values, new_id, &[(face_id, 1.0)]);
+            report.created_faces.push(new_id);
+        }
+
+        report.split_faces.sort();
+        let mut candidates: Vec<u32> = candidates.into_iter().collect();
+        candidates.sort();
+        for node_id in candidates {
+            let used = self
+                .node_face_adj
+                .get_node_adj_faces(node_id)
+                .is_some_and(|set| !set.is_empty());
+            if !used {
+                self.remove_node(node_id);
+                report.removed_nodes.push(node_id);
+            }
+        }
+        report
+    }
+
+    // 坐标处已有节点（如相邻面切割时生成的交点）则直接使用，否则在原面上插值高程并新建
+    fn get_or_create_clip_node(&mut self, face_id: u32, x: f64, y: f64) -> u32 {
+        let rect = self.get_face_rect(face_id).unwrap();
+        let tolerance = (rect.max_x - rect.min_x).max(rect.max_y - rect.min_y) * AREA_EPSILON;
+        let near = Rect::new(x - tolerance, y - tolerance, x + tolerance, y + tolerance);
+        let mut existing = self.node_index.query_rect(&near);
+        existing.sort();
+        if let Some(&id) = existing.first() {
+            return id;
+        }
+        let location = self.get_face_weights(face_id, x, y);
+        let z = location
+            .as_ref()
+            .and_then(|location| location.interpolate(|id| self.node_map.get(&id).map(|n| n.z)))
+            .or_else(|| self.interpolate_z_at(x, y))
+            .or_else(|| {
+                let id = self.find_nearest_node(x, y)?;
+                Some(self.node_map[&id].z)
+            })
+            .unwrap_or(0.0);
+        let id = self.create_node(x, y, z);
+        if let Some(location) = location {
+            self.interpolate_node_values(id, &location.weights);
+        }
+        id
+    }
+
+    // 剖分节点围成的环，三角形绕向与 sign 一致
+    fn triangulate_ring_nodes(&self, ids: &[u32], sign: f64) -> Vec<[u32; 3]> {
+        let ring = self.get_face_points(ids);
+        let mut result = Vec::new();
+        for [a, b, c] in self.triangulate_ring(&ring) {
+            // 数值误差使相邻交点落到同一节点时跳过退化的三角形
+            if ids[a] == ids[b] || ids[b] == ids[c] || ids[c] == ids[a] {
+                continue;
+            }
+            if signed_area(&[ring[a], ring[b], ring[c]]) * sign > 0.0 {
+                result.push([ids[a], ids[b], ids[c]]);
+            } else {
+                result.push([ids[a], ids[c], ids[b]]);
+            }
+        }
+        result
+    }
+
+    // 以环为边界做约束 Delaunay 剖分，不加入新点；返回环上点的下标
+    fn triangulate_ring(&self, ring: &[(f64, f64)]) -> Vec<[usize; 3]> {
+        if ring.len() == 3 {
+            return vec![[0, 1, 2]];
+        }
+        let points: Vec<[f64; 3]> = ring.iter().map(|&(x, y)| [x, y, 0.0]).collect();
+        let (coverage, report) = MeshCoverage::triangulate(
+            String::new(),
+            &[],
+            &[],
+            Some(&points),
+            &[],
+            &TriangulateOptions::default(),
+        );
+        // 环的边未能全部插入时内外判断不可靠，退回以首点为顶点的扇形剖分
+        if !report.is_empty() {
+            return (1..ring.len() - 1).map(|i| [0, i, i + 1]).collect();
+        }
+        // 剖分结果的坐标经过平移，按最近点对应回环上的点
+        let index = |id: &u32| {
+            let node = coverage.node_map[id];
+            (0..ring.len())
+                .min_by(|&a, &b| {
+                    let da = (ring[a].0 - node.x).powi(2) + (ring[a].1 - node.y).powi(2);
+                    let db = (ring[b].0 - node.x).powi(2) + (ring[b].1 - node.y).powi(2);
+                    da.total_cmp(&db)
+                })
+                .unwrap()
+        };
+        let mut face_ids: Vec<u32> = coverage.face_map.keys().copied().collect();
+        face_ids.sort();
+        face_ids
+            .iter()
+            .map(|face_id| {
+                let face = coverage.face_map[face_id];
+                [index(&face.n0), index(&face.n1), index(&face.n2)]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use super::{dataset::DatasetLocation, geometry::signed_area, MeshCoverage};
+
+#[derive(Debug, Clone, Default)]
+pub struct Component {
+    pub face_ids: Vec<u32>,
+    pub node_ids: Vec<u32>,
+    pub area: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComponentReport {
+    // 按面数从多到少排序，面数相同时按面积
+    pub components: Vec<Component>,
+    // 除最大分量外面数不超过 max_island_faces 的分量在 components 中的序号
+    pub islands: Vec<usize>,
+    // 不属于任何面的节点
+    pub orphan_nodes: Vec<u32>,
+}
+
+impl ComponentReport {
+    pub fn is_connected(&self) -> bool {
+        self.components.len() <= 1 && self.orphan_nodes.is_empty()
+    }
+}
+
+impl fmt::Display for ComponentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "components: {}", self.components.len())?;
+        for (i, component) in self.components.iter().enumerate() {
+            writeln!(
+                f,
+                "  #{}: {} faces, {} nodes, area {:.3}{}",
+                i,
+                component.face_ids.len(),
+                component.node_ids.len(),
+                component.area,
+                if self.islands.contains(&i) {
+                    " (island)"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        write!(f, "  orphan nodes: {}", self.orphan_nodes.len())
+    }
+}
+
+impl MeshCoverage {
+    // 通过公共边相连的面的连通分量，按边的节点对判断而不依赖半边，非流形边上的面也连在一起
+    pub fn get_face_components(&self) -> Vec<Vec<u32>> {
+        let mut edge_faces: HashMap<(u32, u32), Vec<u32>> = HashMap::new();
+        for (&face_id, face) in &self.face_map {
+            let ids = face.node_ids();
+            let len = ids.len();
+            for i in 0..len {
+                let (a, b) = (ids[i], ids[(i + 1) % len]);
+                edge_faces
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push(face_id);
+            }
+        }
+
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut components = Vec::new();
+        for seed in face_ids {
+            if !visited.insert(seed) {
+                continue;
+            }
+            let mut component = Vec::new();
+            let mut queue: VecDeque<u32> = VecDeque::from([seed]);
+            while let Some(face_id) = queue.pop_front() {
+                component.push(face_id);
+                let ids = self.face_map[&face_id].node_ids();
+                let len = ids.len();
+                for i in 0..len {
+                    let (a, b) = (ids[i], ids[(i + 1) % len]);
+                    for &other in &edge_faces[&(a.min(b), a.max(b))] {
+                        if visited.insert(other) {
+                            queue.push_back(other);
+                        }
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+
+    // 通过面相连的节点的连通分量，只在一个节点处相接的两部分属于同一分量；孤立节点各自成为一个分量
+    pub fn get_node_components(&self) -> Vec<Vec<u32>> {
+        let mut parent: HashMap<u32, u32> = self.node_map.keys().map(|&id| (id, id)).collect();
+        fn find(parent: &mut HashMap<u32, u32>, id: u32) -> u32 {
+            let mut root = id;
+            while parent[&root] != root {
+                root = parent[&root];
+            }
+            let mut current = id;
+            while current != root {
+                let next = parent[&current];
+                parent.insert(current, root);
+                current = next;
+            }
+            root
+        }
+        for face in self.face_map.values() {
+            let ids: Vec<u32> = face
+                .node_ids()
+                .into_iter()
+                .filter(|id| parent.contains_key(id))
+                .collect();
+            for pair in ids.windows(2) {
+                let (a, b) = (find(&mut parent, pair[0]), find(&mut parent, pair[1]));
+                if a != b {
+                    parent.insert(a.max(b), a.min(b));
+                }
+            }
+        }
+
+        let mut groups: HashMap<u32, Vec<u32>> = HashMap::new();
+        let mut node_ids: Vec<u32> = self.node_map.keys().copied().collect();
+        node_ids.sort();
+        for id in node_ids {
+            let root = find(&mut parent, id);
+            groups.entry(root).or_default().push(id);
+        }
+        let mut components: Vec<Vec<u32>> = groups.into_values().collect();
+        components.sort();
+        components
+    }
+
+    // 面的连通分量及其面积，面数不超过 max_island_faces 的小分量标记为孤岛
+    pub fn get_components(&self, max_island_faces: usize) -> ComponentReport {
+        let mut components: Vec<Component> = self
+            .get_face_components()
+            .into_iter()
+            .map(|face_ids| {
+                let mut node_ids: Vec<u32> = face_ids
+                    .iter()
+                    .flat_map(|id| self.face_map[id].node_ids())
+                    .filter(|id| self.node_map.contains_key(id))
+                    .collect::<HashSet<u32>>()
+                    .into_iter()
+                    .collect();
+                node_ids.sort();
+                let area = face_ids
+                    .iter()
+                    .map(|id| {
+                        let ids: Vec<u32> = self.face_map[id]
+                            .node_ids()
+                            .into_iter()
+                            .filter(|id| self.node_map.contains_key(id))
+                            .collect();
+                        signed_area(&self.get_face_points(&ids)).abs()
+                    })
+                    .sum();
+                Component {
+                    face_ids,
+                    node_ids,
+                    area,
+                }
+            })
+            .collect();
+        components.sort_by(|a, b| {
+            b.face_ids
+                .len()
+                .cmp(&a.face_ids.len())
+                .then(b.area.total_cmp(&a.area))
+                .then(a.face_ids.cmp(&b.face_ids))
+        });
+
+        let islands = (1..components.len())
+            .filter(|&i| components[i].face_ids.len() <= max_island_faces)
+            .collect();
+        ComponentReport {
+            components,
+            islands,
+            orphan_nodes: self.get_orphan_nodes(),
+        }
+    }
+
+    pub fn get_orphan_nodes(&self) -> Vec<u32> {
+        let mut node_ids: Vec<u32> = self
+            .node_map
+            .keys()
+            .copied()
+            .filter(|&id| self.is_orphan_node(id))
+            .collect();
+        node_ids.sort();
+        node_ids
+    }
+
+    fn is_orphan_node(&self, id: u32) -> bool {
+        self.node_face_adj
+            .get_node_adj_faces(id)
+            .is_none_or(|set| set.is_empty())
+    }
+
+    // 删除这些面，以及因此不再属于任何面的节点，返回删除的节点
+    pub fn remove_component(&mut self, face_ids: &[u32]) -> Vec<u32> {
+        let mut node_ids: HashSet<u32> = HashSet::new();
+        for id in face_ids {
+            if let Some(face) = self.face_map.get(id) {
+                node_ids.extend(face.node_ids());
+            }
+            self.remove_face(*id);
+        }
+        let mut removed: Vec<u32> = node_ids
+            .into_iter()
+            .filter(|&id| self.node_map.contains_key(&id) && self.is_orphan_node(id))
+            .collect();
+        removed.sort();
+        for &id in &removed {
+            self.remove_node(id);
+        }
+        removed
+    }
+
+    pub fn remove_orphan_nodes(&mut self) -> Vec<u32> {
+        let node_ids = self.get_orphan_nodes();
+        for &id in &node_ids {
+            self.remove_node(id);
+        }
+        node_ids
+    }
+
+    // 将这些面及其节点复制到新的网格中，保留原有的 id 及其上的数据集
+    pub fn extract_component(&self, face_ids: &[u32], id: String) -> MeshCoverage {
+        let mut coverage = MeshCoverage::new(id);
+        let mut faces: Vec<u32> = face_ids
+            .iter()
+            .copied()
+            .filter(|id| self.face_map.contains_key(id))
+            .collect();
+        faces.sort();
+        faces.dedup();
+        let mut nodes: Vec<u32> = faces
+            .iter()
+            .flat_map(|id| self.face_map[id].node_ids())
+            .filter(|id| self.node_map.contains_key(id))
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+
+        for &node_id in &nodes {
+            let node = self.node_map[&node_id];
+            coverage.create_node_with_id(node_id, node.x, node.y, node.z);
+        }
+        for &face_id in &faces {
+            let face = self.face_map[&face_id];
+            coverage.create_face_with_id(face_id, face.n0, face.n1, face.n2, face.n3);
+        }
+        coverage.generate_half_edges();
+
+        for dataset in self.dataset_map.values() {
+            let mut dataset = dataset.clone();
+            let ids = match dataset.location {
+                DatasetLocation::Node => &nodes,
+                DatasetLocation::Face => &faces,
+            };
+            dataset.renumber(&ids.iter().map(|&id| (id, id)).collect());
+            coverage.add_dataset(dataset);
+        }
+        coverage
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use super::MeshCoverage;
+
+// 等值线经过的一条边，用两端节点 id 从小到大表示
+type EdgeKey = (u32, u32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub level: f64,
+    // 闭合时首尾点不重复
+    pub points: Vec<(f64, f64)>,
+    pub closed: bool,
+}
+
+impl MeshCoverage {
+    // 节点高程范围内 interval 的整数倍
+    pub fn get_contour_levels(&self, interval: f64) -> Vec<f64> {
+        if !(interval > 0.0 && interval.is_finite()) || self.node_map.is_empty() {
+            return Vec::new();
+        }
+        let (min, max) = self
+            .node_map
+            .values()
+            .fold((f64::MAX, f64::MIN), |(min, max), node| {
+                (min.min(node.z), max.max(node.z))
+            });
+        let first = (min / interval).ceil() as i64;
+        let last = (max / interval).floor() as i64;
+        (first..=last).map(|i| i as f64 * interval).collect()
+    }
+
+    pub fn get_contours(&self, interval: f64) -> Vec<Contour> {
+        self.get_contours_at_levels(&self.get_contour_levels(interval))
+    }
+
+    // 按面的节点高程线性插值求等值线，四边形沿 n0-n2 对角线分成两个三角形；
+    // 高程等于等值线值的节点视为在其上方，因此每条边最多与一条等值线相交一次
+    pub fn get_contours_at_levels(&self, levels: &[f64]) -> Vec<Contour> {
+        let mut triangles: Vec<[u32; 3]> = Vec::with_capacity(self.face_map.len() * 2);
+        let mut face_ids: Vec<&u32> = self.face_map.keys().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                continue;
+            }
+            for i in 1..ids.len() - 1 {
+                triangles.push([ids[0], ids[i], ids[i + 1]]);
+            }
+        }
+
+        let mut contours = Vec::new();
+        for &level in levels {
+            let mut points: HashMap<EdgeKey, (f64, f64)> = HashMap::new();
+            let mut segments: Vec<[EdgeKey; 2]> = Vec::new();
+            for triangle in &triangles {
+                let mut ends: Vec<EdgeKey> = Vec::with_capacity(2);
+                for i in 0..3 {
+                    let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                    if let Some(point) = self.get_edge_crossing(a, b, level) {
+                        let key = (a.min(b), a.max(b));
+                        points.insert(key, point);
+                        ends.push(key);
+                    }
+                }
+                if ends.len() == 2 {
+                    segments.push([ends[0], ends[1]]);
+                }
+            }
+            for keys in chain_segments(&segments) {
+                let closed = keys.len() > 2 && keys.first() == keys.last();
+                let mut line: Vec<(f64, f64)> = Vec::with_capacity(keys.len());
+                for key in &keys {
+                    let point = points[key];
+                    // 经过节点时相邻的交点重合
+                    if line.last() != Some(&point) {
+                        line.push(point);
+                    }
+                }
+                if closed && line.len() > 1 && line.first() == line.last() {
+                    line.pop();
+                }
+                if line.len() >= 2 {
+                    contours.push(Contour {
+                        level,
+                        points: line,
+                        closed,
+                    });
+                }
+            }
+        }
+        contours
+    }
+
+    // 边 ab 与等值线的交点，一端不低于 level 且另一端低于 level 时才相交
+    fn get_edge_crossing(&self, a: u32, b: u32, level: f64) -> Option<(f64, f64)> {
+        let (na, nb) = (self.node_map[&a], self.node_map[&b]);
+        if (na.z >= level) == (nb.z >= level) {
+            return None;
+        }
+        let t = (level - na.z) / (nb.z - na.z);
+        Some((na.x + (nb.x - na.x) * t, na.y + (nb.y - na.y) * t))
+    }
+}
+
+// 将共用端点的线段连成折线：先从只连一条线段的端点（网格边界）出发得到开放折线，
+// 剩下的线段都在闭合环上，闭合环首尾的边相同
+fn chain_segments(segments: &[[EdgeKey; 2]]) -> Vec<Vec<EdgeKey>> {
+    let mut adj: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        for key in segment {
+            adj.entry(*key).or_default().push(i);
+        }
+    }
+    let mut used = vec![false; segments.len()];
+    let walk = |start: EdgeKey, used: &mut [bool]| {
+        let mut keys = vec![start];
+        let mut current = start;
+        while let Some(&i) = adj[&current].iter().find(|&&i| !used[i]) {
+            used[i] = true;
+            let [k0, k1] = segments[i];
+            current = if k0 == current { k1 } else { k0 };
+            keys.push(current);
+        }
+        keys
+    };
+
+    let mut lines = Vec::new();
+    let mut starts: Vec<EdgeKey> = adj
+        .iter()
+        .filter(|(_, ids)| ids.len() == 1)
+        .map(|(key, _)| *key)
+        .collect();
+    starts.sort();
+    for start in starts {
+        let keys = walk(start, &mut used);
+        if keys.len() > 1 {
+            lines.push(keys);
+        }
+    }
+    for (i, segment) in segments.iter().enumerate() {
+        if !used[i] {
+            lines.push(walk(segment[0], &mut used));
+        }
+    }
+    lines
+}
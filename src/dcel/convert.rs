@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+
+use super::{
+    geometry::{interior_angles, is_same_winding, signed_area},
+    MeshCoverage,
+};
+
+// 三角形质量：最小内角与 60 度之比
+fn triangle_quality(points: &[(f64, f64)]) -> f64 {
+    let min_angle = interior_angles(points)
+        .into_iter()
+        .fold(f64::MAX, |a, b| a.min(b));
+    min_angle / 60.0
+}
+
+// 四边形质量：1 减去内角偏离 90 度的最大值与 90 之比，非凸时为 0
+fn quad_quality(points: &[(f64, f64)]) -> f64 {
+    let max_deviation = interior_angles(points)
+        .into_iter()
+        .fold(0.0, |a: f64, b| a.max((b - 90.0).abs()));
+    (1.0 - max_deviation / 90.0).max(0.0)
+}
+
+impl MeshCoverage {
+    // 将所有四边形拆分为三角形，返回新建的面
+    pub fn split_all_quads(&mut self) -> Vec<u32> {
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        self.split_quads(&face_ids)
+    }
+
+    // 沿质量较好的对角线将选中的四边形拆分为两个三角形，返回新建的面；引用了不存在的节点的面不拆分
+    pub fn split_quads(&mut self, face_ids: &[u32]) -> Vec<u32> {
+        let mut result = Vec::new();
+        for face_id in face_ids {
+            let face = match self.face_map.get(face_id) {
+                Some(face)
+                    if !face.is_triangle()
+                        && face
+                            .node_ids()
+                            .iter()
+                            .all(|id| self.node_map.contains_key(id)) =>
+                {
+                    *face
+                }
+                _ => continue,
+            };
+            let [n0, n1, n2, n3] = [face.n0, face.n1, face.n2, face.n3];
+            let sign = signed_area(&self.get_face_points(&[n0, n1, n2, n3])).signum();
+
+            // 对角线 n0-n2 与 n1-n3，取两个三角形最小质量较大的一条
+            let mut best: Option<(f64, [[u32; 3]; 2])> = None;
+            for triangles in [[[n0, n1, n2], [n0, n2, n3]], [[n1, n2, n3], [n1, n3, n0]]] {
+                let points_0 = self.get_face_points(&triangles[0]);
+                let points_1 = self.get_face_points(&triangles[1]);
+                if !is_same_winding(&points_0, sign) || !is_same_winding(&points_1, sign) {
+                    continue;
+                }
+                let quality = triangle_quality(&points_0).min(triangle_quality(&points_1));
+                let better = match best {
+                    Some((best_quality, _)) => quality > best_quality,
+                    None => true,
+                };
+                if better {
+                    best = Some((quality, triangles));
+                }
+            }
+
+            if let Some((_, triangles)) = best {
+                let values = self.get_face_values(&[*face_id]);
+                self.remove_face(*face_id);
+                for [a, b, c] in triangles {
+                    let new_id = self.add_face(a, b, c, 0);
+                    self.interpolate_face_values(&values, new_id, &[(*face_id, 1.0)]);
+                    result.push(new_id);
+                }
+            }
+        }
+        result
+    }
+
+    // 将所有相邻三角形按质量从高到低合并为四边形，返回新建的面
+    pub fn merge_all_triangles(&mut self, min_quality: f64) -> Vec<u32> {
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        self.merge_triangles(&face_ids, min_quality)
+    }
+
+    // 将选中的三角形两两合并为凸四边形，质量低于 min_quality（0~1）的组合不合并；
+    // 引用了不存在的节点的三角形不合并
+    pub fn merge_triangles(&mut self, face_ids: &[u32], min_quality: f64) -> Vec<u32> {
+        let selected: HashSet<u32> = face_ids
+            .iter()
+            .filter(|id| {
+                self.face_map.get(id).is_some_and(|face| {
+                    face.is_triangle()
+                        && face
+                            .node_ids()
+                            .iter()
+                            .all(|id| self.node_map.contains_key(id))
+                })
+            })
+            .copied()
+            .collect();
+
+        let mut half_edge_ids: Vec<u32> = self.half_edge_map.keys().copied().collect();
+        half_edge_ids.sort();
+
+        let mut candidates: Vec<(f64, [u32; 2], [u32; 4])> = Vec::new();
+        for id in half_edge_ids {
+            let half_edge = self.half_edge_map[&id];
+            let twin = match self.half_edge_map.get(&half_edge.twin_id) {
+                Some(twin) => *twin,
+                None => continue,
+            };
+            if id > half_edge.twin_id
+                || !selected.contains(&half_edge.face_id)
+                || !selected.contains(&twin.face_id)
+            {
+                continue;
+            }
+            // 三角形 (a, b, c) 与 (b, a, d) 合并为 (a, d, b, c)
+            let a = half_edge.start_id;
+            let b = half_edge.end_id;
+            let c = self.half_edge_map[&half_edge.next_id].end_id;
+            let d = self.half_edge_map[&twin.next_id].end_id;
+            let quad = [a, d, b, c];
+            let points = self.get_face_points(&quad);
+            let sign = signed_area(&self.get_face_points(&[a, b, c])).signum();
+            if !is_same_winding(&points, sign) {
+                continue;
+            }
+            let quality = quad_quality(&points);
+            if quality >= min_quality && quality > 0.0 {
+                candidates.push((quality, [half_edge.face_id, twin.face_id], quad));
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut merged: HashSet<u32> = HashSet::new();
+        let mut result = Vec::new();
+        for (_, [face_id, twin_face_id], [a, d, b, c]) in candidates {
+            if merged.contains(&face_id) || merged.contains(&twin_face_id) {
+                continue;
+            }
+            merged.insert(face_id);
+            merged.insert(twin_face_id);
+            let values = self.get_face_values(&[face_id, twin_face_id]);
+            self.remove_face(face_id);
+            self.remove_face(twin_face_id);
+            let new_id = self.add_face(a, d, b, c);
+            self.interpolate_face_values(&values, new_id, &[(face_id, 0.5), (twin_face_id, 0.5)]);
+            result.push(new_id);
+        }
+        result
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{snapshot::serialize_map, MeshCoverage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DatasetLocation {
+    Node,
+    Face,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DatasetValues {
+    Scalar(#[serde(serialize_with = "serialize_map")] HashMap<u32, f64>),
+    // 矢量按 (x, y) 分量存储，统计与着色使用模
+    Vector(#[serde(serialize_with = "serialize_map")] HashMap<u32, (f64, f64)>),
+}
+
+impl DatasetValues {
+    // 标量值或矢量的模
+    pub fn get(&self, id: u32) -> Option<f64> {
+        match self {
+            DatasetValues::Scalar(map) => map.get(&id).copied(),
+            DatasetValues::Vector(map) => map.get(&id).map(|(x, y)| x.hypot(*y)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            DatasetValues::Scalar(map) => map.len(),
+            DatasetValues::Vector(map) => map.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_vector(&self) -> bool {
+        matches!(self, DatasetValues::Vector(_))
+    }
+
+    fn iter_values(&self) -> Box<dyn Iterator<Item = f64> + '_> {
+        match self {
+            DatasetValues::Scalar(map) => Box::new(map.values().copied()),
+            DatasetValues::Vector(map) => Box::new(map.values().map(|(x, y)| x.hypot(*y))),
+        }
+    }
+
+    fn remove(&mut self, id: u32) {
+        match self {
+            DatasetValues::Scalar(map) => {
+                map.remove(&id);
+            }
+            DatasetValues::Vector(map) => {
+                map.remove(&id);
+            }
+        }
+    }
+
+    // 按权重由 source（None 时为自身）中的值插值出 id 的值，矢量按分量插值；权重涉及的 id 缺值时不设值
+    fn interpolate(&mut self, source: Option<&DatasetValues>, id: u32, weights: &[(u32, f64)]) {
+        let mut value = (0.0, 0.0);
+        for &(i, w) in weights {
+            let v = match source.unwrap_or(self) {
+                DatasetValues::Scalar(map) => map.get(&i).map(|v| (*v, 0.0)),
+                DatasetValues::Vector(map) => map.get(&i).copied(),
+            };
+            let Some((x, y)) = v else {
+                return;
+            };
+            value.0 += x * w;
+            value.1 += y * w;
+        }
+        match self {
+            DatasetValues::Scalar(map) => {
+                map.insert(id, value.0);
+            }
+            DatasetValues::Vector(map) => {
+                map.insert(id, value);
+            }
+        }
+    }
+
+    // 加入 other 中的值，已有的值保留；类型不同时不加入并返回 false
+    fn extend(&mut self, other: &DatasetValues) -> bool {
+        match (self, other) {
+            (DatasetValues::Scalar(map), DatasetValues::Scalar(other)) => {
+                for (id, v) in other {
+                    map.entry(*id).or_insert(*v);
+                }
+                true
+            }
+            (DatasetValues::Vector(map), DatasetValues::Vector(other)) => {
+                for (id, v) in other {
+                    map.entry(*id).or_insert(*v);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // 只保留 ids 上的值
+    fn subset(&self, ids: &[u32]) -> Self {
+        match self {
+            DatasetValues::Scalar(map) => DatasetValues::Scalar(
+                ids.iter()
+                    .filter_map(|id| map.get(id).map(|v| (*id, *v)))
+                    .collect(),
+            ),
+            DatasetValues::Vector(map) => DatasetValues::Vector(
+                ids.iter()
+                    .filter_map(|id| map.get(id).map(|v| (*id, *v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    // 按 旧 id -> 新 id 重新编号，不在 ids 中的值丢弃
+    fn renumber(&self, ids: &HashMap<u32, u32>) -> Self {
+        match self {
+            DatasetValues::Scalar(map) => DatasetValues::Scalar(
+                map.iter()
+                    .filter_map(|(id, v)| ids.get(id).map(|new| (*new, *v)))
+                    .collect(),
+            ),
+            DatasetValues::Vector(map) => DatasetValues::Vector(
+                map.iter()
+                    .filter_map(|(id, v)| ids.get(id).map(|new| (*new, *v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetStep {
+    pub time: f64,
+    pub values: DatasetValues,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatasetStatistics {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    // 参与统计的有限值个数
+    pub count: usize,
+}
+
+impl DatasetStatistics {
+    fn from_values(values: impl Iterator<Item = f64>) -> Option<Self> {
+        let (mut min, mut max, mut sum, mut count) = (f64::MAX, f64::MIN, 0.0, 0);
+        for value in values.filter(|v| v.is_finite()) {
+            min = min.min(value);
+            max = max.max(value);
+            sum += value;
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        Some(Self {
+            min,
+            max,
+            mean: sum / count as f64,
+            count,
+        })
+    }
+}
+
+// 节点或面上的一组计算结果，如水深、流速、水位；不随时间变化时只有一个时间步
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dataset {
+    pub name: String,
+    pub location: DatasetLocation,
+    pub steps: Vec<DatasetStep>,
+}
+
+impl Dataset {
+    pub fn new(name: String, location: DatasetLocation) -> Self {
+        Self {
+            name,
+            location,
+            steps: Vec::new(),
+        }
+    }
+
+    // 按时间顺序插入，时间相同时替换原有的时间步
+    pub fn add_step(&mut self, time: f64, values: DatasetValues) {
+        let i = self.steps.partition_point(|step| step.time < time);
+        let step = DatasetStep { time, values };
+        if self.steps.get(i).map(|step| step.time) == Some(time) {
+            self.steps[i] = step;
+        } else {
+            self.steps.insert(i, step);
+        }
+    }
+
+    pub fn get_times(&self) -> Vec<f64> {
+        self.steps.iter().map(|step| step.time).collect()
+    }
+
+    pub fn is_vector(&self) -> bool {
+        self.steps.iter().any(|step| step.values.is_vector())
+    }
+
+    pub fn get_value(&self, step: usize, id: u32) -> Option<f64> {
+        self.steps.get(step)?.values.get(id)
+    }
+
+    pub fn get_step_statistics(&self, step: usize) -> Option<DatasetStatistics> {
+        DatasetStatistics::from_values(self.steps.get(step)?.values.iter_values())
+    }
+
+    // 所有时间步合在一起的统计，用于各时间步统一色带
+    pub fn get_statistics(&self) -> Option<DatasetStatistics> {
+        DatasetStatistics::from_values(self.steps.iter().flat_map(|step| step.values.iter_values()))
+    }
+
+    pub fn renumber(&mut self, ids: &HashMap<u32, u32>) {
+        for step in &mut self.steps {
+            step.values = step.values.renumber(ids);
+        }
+    }
+}
+
+impl MeshCoverage {
+    // 同名的数据集被替换并返回
+    pub fn add_dataset(&mut self, dataset: Dataset) -> Option<Dataset> {
+        self.dataset_map.insert(dataset.name.clone(), dataset)
+    }
+
+    pub fn remove_dataset(&mut self, name: &str) -> Option<Dataset> {
+        self.dataset_map.remove(name)
+    }
+
+    pub fn get_dataset(&self, name: &str) -> Option<&Dataset> {
+        self.dataset_map.get(name)
+    }
+
+    pub fn get_dataset_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.dataset_map.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // 新节点的值按权重由已有节点插值，如边中点、切割产生的交点
+    pub fn interpolate_node_values(&mut self, node_id: u32, weights: &[(u32, f64)]) {
+        for dataset in self.dataset_map.values_mut() {
+            if dataset.location == DatasetLocation::Node {
+                for step in &mut dataset.steps {
+                    step.values.interpolate(None, node_id, weights);
+                }
+            }
+        }
+    }
+
+    // 只含 face_ids 上的值的面数据集副本；删除面之前保存，供 interpolate_face_values 给新面赋值
+    pub fn get_face_values(&self, face_ids: &[u32]) -> Vec<Dataset> {
+        let mut result: Vec<Dataset> = Vec::new();
+        for dataset in self.dataset_map.values() {
+            if dataset.location == DatasetLocation::Face {
+                result.push(Dataset {
+                    name: dataset.name.clone(),
+                    location: dataset.location,
+                    steps: dataset
+                        .steps
+                        .iter()
+                        .map(|step| DatasetStep {
+                            time: step.time,
+                            values: step.values.subset(face_ids),
+                        })
+                        .collect(),
+                });
+            }
+        }
+        result
+    }
+
+    // 新面的值按权重由 values 中原有面的值插值，如拆分时复制原面的值、合并时取平均
+    pub fn interpolate_face_values(
+        &mut self,
+        values: &[Dataset],
+        face_id: u32,
+        weights: &[(u32, f64)],
+    ) {
+        for source in values {
+            if let Some(dataset) = self.dataset_map.get_mut(&source.name) {
+                for (step, source_step) in dataset.steps.iter_mut().zip(&source.steps) {
+                    step.values
+                        .interpolate(Some(&source_step.values), face_id, weights);
+                }
+            }
+        }
+    }
+
+    // 合并网格时加入 other 的数据集，node_ids、face_ids 为 other 的 id -> 合并后的 id，重合节点保留原有的值；
+    // 返回合并后在部分节点或面上缺值的数据集：只有一侧有、时间步不一致或类型不同
+    pub fn merge_datasets(
+        &mut self,
+        other: &MeshCoverage,
+        node_ids: &HashMap<u32, u32>,
+        face_ids: &HashMap<u32, u32>,
+    ) -> Vec<String> {
+        let mut incomplete: Vec<String> = Vec::new();
+        // 原网格中不来自 other 的节点，other 独有的数据集在这些节点上缺值
+        let merged: HashSet<u32> = node_ids.values().copied().collect();
+        let self_only = self.node_map.keys().any(|id| !merged.contains(id));
+        for name in self.get_dataset_names() {
+            if !other.dataset_map.contains_key(&name) && !other.node_map.is_empty() {
+                incomplete.push(name);
+            }
+        }
+        for name in other.get_dataset_names() {
+            let mut dataset = other.dataset_map[&name].clone();
+            match dataset.location {
+                DatasetLocation::Node => dataset.renumber(node_ids),
+                DatasetLocation::Face => dataset.renumber(face_ids),
+            }
+            let target = match self.dataset_map.get_mut(&name) {
+                Some(target) => target,
+                None => {
+                    if self_only {
+                        incomplete.push(name.clone());
+                    }
+                    self.dataset_map.insert(name, dataset);
+                    continue;
+                }
+            };
+            let mut complete =
+                target.location == dataset.location && target.steps.len() == dataset.steps.len();
+            if target.location == dataset.location {
+                for step in dataset.steps {
+                    let i = target.steps.partition_point(|s| s.time < step.time);
+                    match target.steps.get_mut(i) {
+                        Some(s) if s.time == step.time => {
+                            complete &= s.values.extend(&step.values);
+                        }
+                        _ => {
+                            complete = false;
+                            target.steps.insert(i, step);
+                        }
+                    }
+                }
+            }
+            if !complete {
+                incomplete.push(name);
+            }
+        }
+        incomplete.sort();
+        incomplete.dedup();
+        incomplete
+    }
+
+    // 节点或面删除时一并删除其上的值
+    pub fn remove_dataset_values(&mut self, location: DatasetLocation, id: u32) {
+        for dataset in self.dataset_map.values_mut() {
+            if dataset.location == location {
+                for step in &mut dataset.steps {
+                    step.values.remove(id);
+                }
+            }
+        }
+    }
+}
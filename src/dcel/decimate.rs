@@ -0,0 +1,678 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt,
+};
+
+use super::{
+    geometry::{is_same_winding, signed_area},
+    spatial::Rect,
+    MeshCoverage,
+};
+
+// 对称 4x4 矩阵的上三角：a²、ab、ac、ad、b²、bc、bd、c²、cd、d²
+type Quadric = [f64; 10];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecimateMetric {
+    // 二次误差（Garland & Heckbert），到原相邻面所在平面距离的平方和
+    Quadric,
+    // 被删除节点到新表面的最大竖向偏差
+    Vertical,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecimateOptions {
+    pub metric: DecimateMetric,
+    // 面数不超过该值时停止
+    pub target_faces: Option<usize>,
+    // 被删除节点到新表面的竖向偏差不得超过该值
+    pub max_error: Option<f64>,
+    // 断裂线按节点 id 依次给出，线上的节点都保留，可由 get_breakline_nodes 从折线得到
+    pub breaklines: Vec<Vec<u32>>,
+    pub locked_nodes: HashSet<u32>,
+}
+
+impl Default for DecimateOptions {
+    fn default() -> Self {
+        Self {
+            metric: DecimateMetric::Quadric,
+            target_faces: None,
+            max_error: None,
+            breaklines: Vec::new(),
+            locked_nodes: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DecimateReport {
+    pub faces_before: usize,
+    pub faces_after: usize,
+    pub removed_nodes: Vec<u32>,
+    // 本次及之前各次简化删除的全部原始节点到简化后表面的竖向偏差
+    pub max_error: f64,
+    pub rms_error: f64,
+}
+
+impl fmt::Display for DecimateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "faces {} -> {}, {} nodes removed, max error {:.4}, rms error {:.4}",
+            self.faces_before,
+            self.faces_after,
+            self.removed_nodes.len(),
+            self.max_error,
+            self.rms_error
+        )
+    }
+}
+
+// 将 remove_id 合并到 keep_id 的候选，cost 小的先收缩，相同时先收缩短边
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    length2: f64,
+    remove_id: u32,
+    keep_id: u32,
+    version: u32,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then(other.length2.total_cmp(&self.length2))
+            .then(other.remove_id.cmp(&self.remove_id))
+    }
+}
+
+struct Decimation {
+    metric: DecimateMetric,
+    max_error: Option<f64>,
+    locked: HashSet<u32>,
+    // 引用了不存在的节点的面上的节点，既不删除也不作为保留节点
+    broken: HashSet<u32>,
+    // 局部坐标原点，减小大坐标下二次误差的舍入
+    origin: (f64, f64),
+    quadrics: HashMap<u32, Quadric>,
+    // 已删除的节点 (x, y, z) 按所在的面存放
+    points: HashMap<u32, Vec<[f64; 3]>>,
+    versions: HashMap<u32, u32>,
+}
+
+impl Decimation {
+    // 两端二次误差之和在保留节点位置处的值
+    fn get_quadric_cost(&self, remove_id: u32, keep_id: u32, (x, y, z): (f64, f64, f64)) -> f64 {
+        let zero = [0.0; 10];
+        let q0 = self.quadrics.get(&remove_id).unwrap_or(&zero);
+        let q1 = self.quadrics.get(&keep_id).unwrap_or(&zero);
+        let v = [x - self.origin.0, y - self.origin.1, z, 1.0];
+        let mut cost = 0.0;
+        let mut k = 0;
+        for i in 0..4 {
+            for j in i..4 {
+                let factor = if i == j { 1.0 } else { 2.0 };
+                cost += (q0[k] + q1[k]) * factor * v[i] * v[j];
+                k += 1;
+            }
+        }
+        cost.max(0.0)
+    }
+}
+
+impl MeshCoverage {
+    // 逐次将内部节点合并到相邻节点（半边收缩），保留的节点位置不变；
+    // 边界节点、断裂线节点、引用了不存在的节点的面上的节点及 locked_nodes 不删除，
+    // 达到目标面数或没有满足误差限的收缩时停止；
+    // 之前各次简化删除的节点保存在 decimated_points 中，误差限同样对它们检查，多次简化的偏差不会累积
+    pub fn decimate(&mut self, options: &DecimateOptions) -> DecimateReport {
+        let faces_before = self.face_map.len();
+        let mut locked: HashSet<u32> = options.locked_nodes.clone();
+        locked.extend(options.breaklines.iter().flatten().copied());
+        locked.extend(
+            self.node_map
+                .keys()
+                .copied()
+                .filter(|&id| self.is_boundary_node(id)),
+        );
+        let mut broken: HashSet<u32> = HashSet::new();
+        for face in self.face_map.values() {
+            let ids = face.node_ids();
+            if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                broken.extend(ids);
+            }
+        }
+        locked.extend(broken.iter().copied());
+        let bbox3 = self.get_bbox3();
+        let mut decimation = Decimation {
+            metric: options.metric,
+            max_error: options.max_error,
+            locked,
+            broken,
+            origin: (
+                (bbox3.min_x + bbox3.max_x) / 2.0,
+                (bbox3.min_y + bbox3.max_y) / 2.0,
+            ),
+            quadrics: HashMap::new(),
+            points: HashMap::new(),
+            versions: HashMap::new(),
+        };
+        if options.metric == DecimateMetric::Quadric {
+            decimation.quadrics = self.get_node_quadrics(decimation.origin);
+        }
+        // 已不在网格内的点（如裁剪掉的部分）不再检查
+        for p in std::mem::take(&mut self.decimated_points) {
+            if let Some(location) = self.locate_point(p[0], p[1]) {
+                decimation
+                    .points
+                    .entry(location.face_id)
+                    .or_default()
+                    .push(p);
+            }
+        }
+
+        let mut node_ids: Vec<u32> = self
+            .node_map
+            .keys()
+            .copied()
+            .filter(|id| !decimation.locked.contains(id))
+            .collect();
+        node_ids.sort();
+        let mut heap: BinaryHeap<Collapse> = BinaryHeap::new();
+        for node_id in node_ids {
+            heap.extend(self.get_best_collapse(node_id, &decimation));
+        }
+
+        let mut removed_nodes = Vec::new();
+        let target_faces = options.target_faces.unwrap_or(0);
+        while self.face_map.len() > target_faces {
+            let collapse = match heap.pop() {
+                Some(collapse) => collapse,
+                None => break,
+            };
+            if !self.node_map.contains_key(&collapse.remove_id)
+                || decimation
+                    .versions
+                    .get(&collapse.remove_id)
+                    .copied()
+                    .unwrap_or(0)
+                    != collapse.version
+            {
+                continue;
+            }
+            if !self.apply_collapse(&collapse, &mut decimation) {
+                *decimation.versions.entry(collapse.remove_id).or_default() += 1;
+                continue;
+            }
+            removed_nodes.push(collapse.remove_id);
+
+            // 保留节点周围的面已改变，重新计算其一环内节点的候选
+            let mut affected: Vec<u32> = self
+                .get_node_neighbours(collapse.keep_id)
+                .into_iter()
+                .collect();
+            affected.push(collapse.keep_id);
+            affected.sort();
+            for node_id in affected {
+                *decimation.versions.entry(node_id).or_default() += 1;
+                if !decimation.locked.contains(&node_id) {
+                    heap.extend(self.get_best_collapse(node_id, &decimation));
+                }
+            }
+        }
+
+        let (mut max_error, mut sum2, mut count) = (0.0_f64, 0.0, 0);
+        let mut face_ids: Vec<u32> = decimation.points.keys().copied().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            let ids = self.face_map[&face_id].node_ids();
+            for p in decimation.points.remove(&face_id).unwrap_or_default() {
+                if let Some(error) = self.get_vertical_error(&ids, &p) {
+                    max_error = max_error.max(error);
+                    sum2 += error * error;
+                    count += 1;
+                }
+                self.decimated_points.push(p);
+            }
+        }
+        DecimateReport {
+            faces_before,
+            faces_after: self.face_map.len(),
+            removed_nodes,
+            max_error,
+            rms_error: if count > 0 {
+                (sum2 / count as f64).sqrt()
+            } else {
+                0.0
+            },
+        }
+    }
+
+    // 折线经过的面的全部节点，按经过的先后排列；作为断裂线保留时折线处的面都不改变
+    pub fn get_breakline_nodes(&self, polyline: &[(f64, f64)]) -> Vec<u32> {
+        let mut seen: HashSet<u32> = HashSet::new();
+        let mut node_ids = Vec::new();
+        for pair in polyline.windows(2) {
+            for face_id in self.find_faces_along_segment(pair[0], pair[1]) {
+                for id in self.face_map[&face_id].node_ids() {
+                    if seen.insert(id) {
+                        node_ids.push(id);
+                    }
+                }
+            }
+        }
+        node_ids
+    }
+
+    // 各节点相邻面所在平面的二次误差之和
+    fn get_node_quadrics(&self, origin: (f64, f64)) -> HashMap<u32, Quadric> {
+        let mut quadrics: HashMap<u32, Quadric> = HashMap::new();
+        for face in self.face_map.values() {
+            let ids = face.node_ids();
+            if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                continue;
+            }
+            // Newell 法求法向，四边形不共面时为平均平面
+            let points: Vec<[f64; 3]> = ids
+                .iter()
+                .map(|id| {
+                    let node = self.node_map[id];
+                    [node.x - origin.0, node.y - origin.1, node.z]
+                })
+                .collect();
+            let (mut a, mut b, mut c) = (0.0, 0.0, 0.0);
+            let mut center = [0.0; 3];
+            for (i, p) in points.iter().enumerate() {
+                let q = points[(i + 1) % points.len()];
+                a += (p[1] - q[1]) * (p[2] + q[2]);
+                b += (p[2] - q[2]) * (p[0] + q[0]);
+                c += (p[0] - q[0]) * (p[1] + q[1]);
+                for (sum, value) in center.iter_mut().zip(p) {
+                    *sum += value / points.len() as f64;
+                }
+            }
+            let len = (a * a + b * b + c * c).sqrt();
+            if len == 0.0 {
+                continue;
+            }
+            let (a, b, c) = (a / len, b / len, c / len);
+            let d = -(a * center[0] + b * center[1] + c * center[2]);
+            let plane = [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ];
+            for id in ids {
+                let quadric = quadrics.entry(id).or_insert([0.0; 10]);
+                for (sum, value) in quadric.iter_mut().zip(&plane) {
+                    *sum += value;
+                }
+            }
+        }
+        quadrics
+    }
+
+    // 节点合并到各相邻节点中代价最小的一个，没有可行的收缩时为 None
+    fn get_best_collapse(&self, remove_id: u32, decimation: &Decimation) -> Option<Collapse> {
+        let node = *self.node_map.get(&remove_id)?;
+        let version = decimation.versions.get(&remove_id).copied().unwrap_or(0);
+        let mut candidates: Vec<Collapse> = self
+            .get_node_neighbours(remove_id)
+            .into_iter()
+            .filter(|keep_id| !decimation.broken.contains(keep_id))
+            .map(|keep_id| {
+                let keep = self.node_map[&keep_id];
+                Collapse {
+                    cost: match decimation.metric {
+                        DecimateMetric::Vertical => 0.0,
+                        DecimateMetric::Quadric => decimation.get_quadric_cost(
+                            remove_id,
+                            keep_id,
+                            (keep.x, keep.y, keep.z),
+                        ),
+                    },
+                    length2: (keep.x - node.x).powi(2) + (keep.y - node.y).powi(2),
+                    remove_id,
+                    keep_id,
+                    version,
+                }
+            })
+            .collect();
+        // 二次误差不依赖竖向偏差，按代价从小到大检查，第一个可行的即为最优
+        candidates.sort_by(|a, b| b.cmp(a).then(a.keep_id.cmp(&b.keep_id)));
+
+        let mut best: Option<Collapse> = None;
+        for mut candidate in candidates {
+            let error = match self.get_collapse_error(remove_id, candidate.keep_id, decimation) {
+                Some(error) => error,
+                None => continue,
+            };
+            if decimation.max_error.is_some_and(|max| error > max) {
+                continue;
+            }
+            match decimation.metric {
+                DecimateMetric::Quadric => return Some(candidate),
+                DecimateMetric::Vertical => {
+                    candidate.cost = error;
+                    if best.is_none_or(|best| candidate > best) {
+                        best = Some(candidate);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // 合并后 remove_id 周围已删除的节点及 remove_id 本身到新面的最大竖向偏差；
+    // 合并会破坏拓扑、使面翻转或有点落在新面之外时为 None
+    fn get_collapse_error(
+        &self,
+        remove_id: u32,
+        keep_id: u32,
+        decimation: &Decimation,
+    ) -> Option<f64> {
+        let face_ids = self.node_face_adj.get_node_adj_faces(remove_id)?;
+
+        // 连接条件：两端共同的相邻节点只能是公共边两侧三角形的对顶点
+        let mut opposite: HashSet<u32> = HashSet::new();
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            if ids.len() == 3 && ids.contains(&keep_id) {
+                opposite.extend(ids.iter().filter(|&&id| id != remove_id && id != keep_id));
+            }
+        }
+        let common: HashSet<u32> = self
+            .get_node_neighbours(remove_id)
+            .intersection(&self.get_node_neighbours(keep_id))
+            .copied()
+            .collect();
+        if common != opposite {
+            return None;
+        }
+
+        let keep = self.node_map[&keep_id];
+        let mut faces = Vec::new();
+        let mut points = vec![{
+            let node = self.node_map[&remove_id];
+            [node.x, node.y, node.z]
+        }];
+        let mut face_ids: Vec<u32> = face_ids.iter().copied().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            let old_ids = self.face_map[&face_id].node_ids();
+            if let Some(face_points) = decimation.points.get(&face_id) {
+                points.extend(face_points.iter().copied());
+            }
+            let mut ids: Vec<u32> = Vec::new();
+            for &id in &old_ids {
+                let id = if id == remove_id { keep_id } else { id };
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+            if ids.len() > 1 && ids.first() == ids.last() {
+                ids.pop();
+            }
+            if ids.len() < 3 {
+                continue;
+            }
+            if ids.iter().collect::<HashSet<_>>().len() < ids.len() {
+                return None;
+            }
+            let sign = signed_area(&self.get_face_points(&old_ids)).signum();
+            let new_points: Vec<(f64, f64)> = ids
+                .iter()
+                .map(|id| {
+                    let node = if *id == keep_id {
+                        keep
+                    } else {
+                        self.node_map[id]
+                    };
+                    (node.x, node.y)
+                })
+                .collect();
+            // 四边形还要求各角都是凸角，凹四边形会与相邻面重叠
+            if !is_same_winding(&new_points, sign)
+                || (new_points.len() == 4
+                    && (0..4).any(|i| {
+                        let corner = [
+                            new_points[i],
+                            new_points[(i + 1) % 4],
+                            new_points[(i + 2) % 4],
+                        ];
+                        !is_same_winding(&corner, sign)
+                    }))
+            {
+                return None;
+            }
+            // 先用略放大的外包矩形排除，减少插值权重的计算
+            let mut rect = Rect::from_points(&new_points)?;
+            let margin = (rect.max_x - rect.min_x).max(rect.max_y - rect.min_y) * 1e-6;
+            rect = Rect::new(
+                rect.min_x - margin,
+                rect.min_y - margin,
+                rect.max_x + margin,
+                rect.max_y + margin,
+            );
+            faces.push((ids, rect));
+        }
+
+        let mut max_error: f64 = 0.0;
+        for p in &points {
+            let error = faces
+                .iter()
+                .filter(|(_, rect)| rect.contains_point(p[0], p[1]))
+                .find_map(|(ids, _)| self.get_vertical_error(ids, p))?;
+            max_error = max_error.max(error);
+        }
+        Some(max_error)
+    }
+
+    fn get_vertical_error(&self, node_ids: &[u32], p: &[f64; 3]) -> Option<f64> {
+        let weights = self.get_weights(node_ids, p[0], p[1])?;
+        let z: f64 = node_ids
+            .iter()
+            .zip(weights)
+            .map(|(id, w)| self.node_map[id].z * w)
+            .sum();
+        Some((p[2] - z).abs())
+    }
+
+    // 执行收缩并把已删除的点重新分配到保留节点周围的新面上
+    fn apply_collapse(&mut self, collapse: &Collapse, decimation: &mut Decimation) -> bool {
+        let half_edge_id = match self
+            .iter_node_half_edges(collapse.keep_id)
+            .find(|id| self.half_edge_map[id].end_id == collapse.remove_id)
+        {
+            Some(id) => id,
+            None => return false,
+        };
+        let mut old_face_ids: HashSet<u32> = HashSet::new();
+        for node_id in [collapse.keep_id, collapse.remove_id] {
+            if let Some(set) = self.node_face_adj.get_node_adj_faces(node_id) {
+                old_face_ids.extend(set.iter().copied());
+            }
+        }
+        let mut points: Vec<[f64; 3]> = Vec::new();
+        let node = self.node_map[&collapse.remove_id];
+        let keep = self.node_map[&collapse.keep_id];
+        if self
+            .collapse_edge_to(half_edge_id, keep.x, keep.y, keep.z)
+            .is_none()
+        {
+            return false;
+        }
+        for face_id in old_face_ids {
+            points.extend(decimation.points.remove(&face_id).unwrap_or_default());
+        }
+        points.push([node.x, node.y, node.z]);
+
+        let mut face_ids: Vec<u32> = self
+            .node_face_adj
+            .get_node_adj_faces(collapse.keep_id)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default();
+        face_ids.sort();
+        for p in points {
+            let face_id = face_ids
+                .iter()
+                .copied()
+                .find(|id| {
+                    self.get_face_rect(*id)
+                        .is_some_and(|rect| rect.contains_point(p[0], p[1]))
+                        && self
+                            .get_weights(&self.face_map[id].node_ids(), p[0], p[1])
+                            .is_some()
+                })
+                .or_else(|| self.locate_point(p[0], p[1]).map(|l| l.face_id));
+            if let Some(face_id) = face_id {
+                decimation.points.entry(face_id).or_default().push(p);
+            }
+        }
+
+        if decimation.metric == DecimateMetric::Quadric {
+            if let Some(q) = decimation.quadrics.remove(&collapse.remove_id) {
+                let quadric = decimation
+                    .quadrics
+                    .entry(collapse.keep_id)
+                    .or_insert([0.0; 10]);
+                for (sum, value) in quadric.iter_mut().zip(&q) {
+                    *sum += value;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_z(x: f64, y: f64) -> f64 {
+        (x * 0.7).sin() * (y * 0.5).cos() * 3.0 + 0.1 * x * y
+    }
+
+    // n x n 个方格，每格沿对角线分为两个三角形
+    fn bumpy(n: usize) -> MeshCoverage {
+        let mut coverage = MeshCoverage::new("bumpy".to_string());
+        let mut ids = Vec::new();
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, y) = (i as f64, j as f64);
+                ids.push(coverage.create_node(x, y, get_z(x, y)));
+            }
+        }
+        let id = |i: usize, j: usize| ids[j * (n + 1) + i];
+        for j in 0..n {
+            for i in 0..n {
+                coverage.create_face(id(i, j), id(i + 1, j), id(i + 1, j + 1), 0);
+                coverage.create_face(id(i, j), id(i + 1, j + 1), id(i, j + 1), 0);
+            }
+        }
+        coverage.generate_half_edges();
+        coverage
+    }
+
+    fn get_true_error(coverage: &MeshCoverage, points: &[[f64; 3]]) -> f64 {
+        points
+            .iter()
+            .map(|p| {
+                let location = coverage.locate_point(p[0], p[1]).unwrap();
+                let z: f64 = location
+                    .weights
+                    .iter()
+                    .map(|(id, w)| coverage.node_map[id].z * w)
+                    .sum();
+                (p[2] - z).abs()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn repeated_decimation_keeps_error() {
+        let mut coverage = bumpy(16);
+        let original: Vec<[f64; 3]> = coverage
+            .node_map
+            .values()
+            .map(|node| [node.x, node.y, node.z])
+            .collect();
+        for max_error in [0.05, 0.1, 0.1, 0.2] {
+            let options = DecimateOptions {
+                target_faces: Some(coverage.face_map.len() / 2),
+                max_error: Some(max_error),
+                ..Default::default()
+            };
+            let report = coverage.decimate(&options);
+            let error = get_true_error(&coverage, &original);
+            assert!(error <= max_error + 1e-9, "{}", report);
+            assert!((report.max_error - error).abs() < 1e-9, "{}", report);
+        }
+        assert_eq!(
+            coverage.decimated_points.len() + coverage.node_map.len(),
+            original.len()
+        );
+    }
+
+    #[test]
+    fn breakline_faces_unchanged() {
+        let mut coverage = bumpy(10);
+        let polyline = [(0.5, 5.2), (9.5, 5.3)];
+        let crossed: Vec<Vec<u32>> = coverage
+            .find_faces_along_segment(polyline[0], polyline[1])
+            .iter()
+            .map(|id| coverage.face_map[id].node_ids())
+            .collect();
+        let options = DecimateOptions {
+            breaklines: vec![coverage.get_breakline_nodes(&polyline)],
+            ..Default::default()
+        };
+        let report = coverage.decimate(&options);
+        assert!(!report.removed_nodes.is_empty());
+        let faces: HashSet<Vec<u32>> = coverage.face_map.values().map(|f| f.node_ids()).collect();
+        assert!(crossed.iter().all(|ids| faces.contains(ids)));
+    }
+
+    #[test]
+    fn faces_with_missing_nodes_are_kept() {
+        for metric in [DecimateMetric::Quadric, DecimateMetric::Vertical] {
+            let mut coverage = bumpy(6);
+            let (a, b) = (9, 10);
+            let face_id = coverage.create_face(a, 99, b, 0);
+            coverage.generate_half_edges();
+            let options = DecimateOptions {
+                metric,
+                max_error: Some(1.0),
+                ..Default::default()
+            };
+            let report = coverage.decimate(&options);
+            assert!(!report.removed_nodes.is_empty());
+            assert!(coverage.node_map.contains_key(&a) && coverage.node_map.contains_key(&b));
+            assert_eq!(coverage.face_map[&face_id].node_ids(), vec![a, 99, b]);
+        }
+    }
+}
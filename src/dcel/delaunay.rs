@@ -0,0 +1,977 @@
+use std::{collections::VecDeque, fmt};
+
+use super::MeshCoverage;
+
+const NONE: usize = usize::MAX;
+
+// 约束边类型，边界优先于断裂线
+const FREE: u8 = 0;
+const BREAKLINE: u8 = 1;
+const BOUNDARY: u8 = 2;
+
+// 最小角超过该值时加密可能无法终止
+const MAX_MIN_ANGLE: f64 = 33.0;
+
+#[derive(Debug, Clone)]
+pub struct TriangulateOptions {
+    // 最小内角，单位为度
+    pub min_angle: Option<f64>,
+    // 最大面积
+    pub max_area: Option<f64>,
+    // 加密时最多插入的点数
+    pub max_steiner_points: usize,
+}
+
+impl Default for TriangulateOptions {
+    fn default() -> Self {
+        Self {
+            min_angle: None,
+            max_area: None,
+            max_steiner_points: 1_000_000,
+        }
+    }
+}
+
+fn orient(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+// d 在逆时针三角形 abc 的外接圆内时为正
+fn in_circle(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> f64 {
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+    let ad = adx * adx + ady * ady;
+    let bd = bdx * bdx + bdy * bdy;
+    let cd = cdx * cdx + cdy * cdy;
+    adx * (bdy * cd - bd * cdy) - ady * (bdx * cd - bd * cdx) + ad * (bdx * cdy - bdy * cdx)
+}
+
+fn distance2(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+// 未能插入的输入，坐标为原坐标
+#[derive(Debug, Clone, Default)]
+pub struct TriangulateReport {
+    pub missing_points: Vec<[f64; 3]>,
+    pub missing_breaklines: Vec<([f64; 3], [f64; 3])>,
+    // 边界及孔洞的线段；有缺失时内外判断不可靠，结果可能缺少面或为空
+    pub missing_boundaries: Vec<([f64; 3], [f64; 3])>,
+}
+
+impl TriangulateReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing_points.is_empty()
+            && self.missing_breaklines.is_empty()
+            && self.missing_boundaries.is_empty()
+    }
+}
+
+impl fmt::Display for TriangulateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "all constraints inserted.");
+        }
+        writeln!(f, "triangulation problems:")?;
+        writeln!(f, "  missing points: {}", self.missing_points.len())?;
+        writeln!(
+            f,
+            "  missing breakline segments: {}",
+            self.missing_breaklines.len()
+        )?;
+        write!(
+            f,
+            "  missing boundary segments: {}",
+            self.missing_boundaries.len()
+        )
+    }
+}
+
+// 点集的凸包，逆时针
+fn convex_hull(points: &[[f64; 3]]) -> Vec<[f64; 3]> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    sorted.dedup_by(|a, b| a[0] == b[0] && a[1] == b[1]);
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut hull: Vec<[f64; 3]> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in &sorted {
+            while hull.len() >= start + 2
+                && orient(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+        if pass == 0 {
+            sorted.reverse();
+        }
+    }
+    hull
+}
+
+struct Triangulation {
+    points: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+    // 第 i 条边 (v[i], v[i + 1]) 对面的三角形
+    neighbours: Vec<[usize; 3]>,
+    constraints: Vec<[u8; 3]>,
+    inside: Vec<bool>,
+    vertex_triangle: Vec<usize>,
+    last: usize,
+    tolerance: f64,
+}
+
+impl Triangulation {
+    fn new(extent: f64) -> Self {
+        let size = extent.max(1.0) * 100.0;
+        Self {
+            points: vec![[-size, -size, 0.0], [size, -size, 0.0], [0.0, size, 0.0]],
+            triangles: vec![[0, 1, 2]],
+            neighbours: vec![[NONE; 3]],
+            constraints: vec![[FREE; 3]],
+            inside: vec![false],
+            vertex_triangle: vec![0, 0, 0],
+            last: 0,
+            tolerance: extent.max(1.0) * 1e-10,
+        }
+    }
+
+    fn point(&self, t: usize, i: usize) -> [f64; 3] {
+        self.points[self.triangles[t][i % 3]]
+    }
+
+    // 到直线 ab 的有向距离，左侧为正
+    fn side(&self, a: [f64; 3], b: [f64; 3], p: [f64; 3]) -> f64 {
+        let len = distance2(a, b).sqrt();
+        if len == 0.0 {
+            return 0.0;
+        }
+        orient(a, b, p) / len
+    }
+
+    fn set_triangle(
+        &mut self,
+        t: usize,
+        vertices: [usize; 3],
+        neighbours: [usize; 3],
+        constraints: [u8; 3],
+    ) {
+        if t == self.triangles.len() {
+            self.triangles.push(vertices);
+            self.neighbours.push(neighbours);
+            self.constraints.push(constraints);
+            self.inside.push(false);
+        } else {
+            self.triangles[t] = vertices;
+            self.neighbours[t] = neighbours;
+            self.constraints[t] = constraints;
+        }
+        for i in 0..3 {
+            self.vertex_triangle[vertices[i]] = t;
+            // 邻接三角形指回 t
+            let n = neighbours[i];
+            if n != NONE && n < self.triangles.len() {
+                let a = vertices[i];
+                let b = vertices[(i + 1) % 3];
+                for j in 0..3 {
+                    if self.triangles[n][j] == b && self.triangles[n][(j + 1) % 3] == a {
+                        self.neighbours[n][j] = t;
+                    }
+                }
+            }
+        }
+    }
+
+    fn edge_index(&self, t: usize, a: usize, b: usize) -> usize {
+        (0..3)
+            .find(|&i| self.triangles[t][i] == a && self.triangles[t][(i + 1) % 3] == b)
+            .unwrap()
+    }
+
+    // 包含 p 的三角形：(三角形, 所在边, 重合的顶点)
+    fn locate(&mut self, p: [f64; 3]) -> Option<(usize, Option<usize>, Option<usize>)> {
+        let mut t = self.last;
+        let mut step = 0;
+        loop {
+            step += 1;
+            if step > self.triangles.len() + 3 {
+                // 退化情况下改为遍历
+                t = (0..self.triangles.len()).find(|&t| {
+                    (0..3).all(|i| {
+                        self.side(self.point(t, i), self.point(t, i + 1), p) >= -self.tolerance
+                    })
+                })?;
+                break;
+            }
+            let mut next = None;
+            for k in 0..3 {
+                let i = (k + step) % 3;
+                if self.side(self.point(t, i), self.point(t, i + 1), p) < -self.tolerance {
+                    next = Some(self.neighbours[t][i]);
+                    break;
+                }
+            }
+            match next {
+                None => break,
+                Some(NONE) => return None,
+                Some(n) => t = n,
+            }
+        }
+        self.last = t;
+        for i in 0..3 {
+            if distance2(self.point(t, i), p) <= self.tolerance * self.tolerance {
+                return Some((t, None, Some(self.triangles[t][i])));
+            }
+        }
+        for i in 0..3 {
+            if self.side(self.point(t, i), self.point(t, i + 1), p).abs() <= self.tolerance {
+                return Some((t, Some(i), None));
+            }
+        }
+        Some((t, None, None))
+    }
+
+    fn insert_point(&mut self, p: [f64; 3]) -> Option<usize> {
+        match self.locate(p)? {
+            (_, _, Some(v)) => Some(v),
+            (t, Some(i), None) => Some(self.split_edge(t, i, p)),
+            (t, None, None) => Some(self.split_triangle(t, p)),
+        }
+    }
+
+    fn split_triangle(&mut self, t: usize, p: [f64; 3]) -> usize {
+        let v = self.points.len();
+        self.points.push(p);
+        self.vertex_triangle.push(t);
+
+        let [a, b, c] = self.triangles[t];
+        let [n_ab, n_bc, n_ca] = self.neighbours[t];
+        let [c_ab, c_bc, c_ca] = self.constraints[t];
+        let inside = self.inside[t];
+        let t1 = self.triangles.len();
+        let t2 = t1 + 1;
+        self.set_triangle(t, [a, b, v], [n_ab, t1, t2], [c_ab, FREE, FREE]);
+        self.set_triangle(t1, [b, c, v], [n_bc, t2, t], [c_bc, FREE, FREE]);
+        self.set_triangle(t2, [c, a, v], [n_ca, t, t1], [c_ca, FREE, FREE]);
+        self.inside[t1] = inside;
+        self.inside[t2] = inside;
+
+        self.legalize(vec![(t, 0), (t1, 0), (t2, 0)], v);
+        v
+    }
+
+    fn split_edge(&mut self, t: usize, i: usize, p: [f64; 3]) -> usize {
+        let v = self.points.len();
+        self.points.push(p);
+        self.vertex_triangle.push(t);
+
+        let a = self.triangles[t][i];
+        let b = self.triangles[t][(i + 1) % 3];
+        let c = self.triangles[t][(i + 2) % 3];
+        let u = self.neighbours[t][i];
+        let c_ab = self.constraints[t][i];
+        let n_bc = self.neighbours[t][(i + 1) % 3];
+        let n_ca = self.neighbours[t][(i + 2) % 3];
+        let c_bc = self.constraints[t][(i + 1) % 3];
+        let c_ca = self.constraints[t][(i + 2) % 3];
+
+        let t1 = self.triangles.len();
+        let inside_t = self.inside[t];
+        let mut stack = vec![(t, 0), (t1, 0)];
+        if u == NONE {
+            self.set_triangle(t, [c, a, v], [n_ca, NONE, t1], [c_ca, c_ab, FREE]);
+            self.set_triangle(t1, [b, c, v], [n_bc, t, NONE], [c_bc, FREE, c_ab]);
+            self.inside[t1] = inside_t;
+        } else {
+            let j = self.edge_index(u, b, a);
+            let d = self.triangles[u][(j + 2) % 3];
+            let n_ad = self.neighbours[u][(j + 1) % 3];
+            let n_db = self.neighbours[u][(j + 2) % 3];
+            let c_ad = self.constraints[u][(j + 1) % 3];
+            let c_db = self.constraints[u][(j + 2) % 3];
+            let inside_u = self.inside[u];
+            let u1 = t1 + 1;
+            self.set_triangle(t, [c, a, v], [n_ca, u, t1], [c_ca, c_ab, FREE]);
+            self.set_triangle(t1, [b, c, v], [n_bc, t, u1], [c_bc, FREE, c_ab]);
+            self.set_triangle(u, [a, d, v], [n_ad, u1, t], [c_ad, FREE, c_ab]);
+            self.set_triangle(u1, [d, b, v], [n_db, t1, u], [c_db, c_ab, FREE]);
+            self.inside[t1] = inside_t;
+            self.inside[u1] = inside_u;
+            stack.push((u, 0));
+            stack.push((u1, 0));
+        }
+
+        self.legalize(stack, v);
+        v
+    }
+
+    // 翻转 t 的第 i 条边，返回新对角线所在的两个三角形，新对角线为两者的第 2 条边
+    fn flip(&mut self, t: usize, i: usize) -> (usize, usize) {
+        let a = self.triangles[t][i];
+        let b = self.triangles[t][(i + 1) % 3];
+        let c = self.triangles[t][(i + 2) % 3];
+        let u = self.neighbours[t][i];
+        let j = self.edge_index(u, b, a);
+        let d = self.triangles[u][(j + 2) % 3];
+
+        let n_bc = self.neighbours[t][(i + 1) % 3];
+        let n_ca = self.neighbours[t][(i + 2) % 3];
+        let c_bc = self.constraints[t][(i + 1) % 3];
+        let c_ca = self.constraints[t][(i + 2) % 3];
+        let n_ad = self.neighbours[u][(j + 1) % 3];
+        let n_db = self.neighbours[u][(j + 2) % 3];
+        let c_ad = self.constraints[u][(j + 1) % 3];
+        let c_db = self.constraints[u][(j + 2) % 3];
+
+        self.set_triangle(t, [c, a, d], [n_ca, n_ad, u], [c_ca, c_ad, FREE]);
+        self.set_triangle(u, [d, b, c], [n_db, n_bc, t], [c_db, c_bc, FREE]);
+        (t, u)
+    }
+
+    fn is_delaunay(&self, t: usize, i: usize) -> bool {
+        let u = self.neighbours[t][i];
+        if u == NONE || self.constraints[t][i] != FREE {
+            return true;
+        }
+        let a = self.triangles[t][i];
+        let b = self.triangles[t][(i + 1) % 3];
+        let j = self.edge_index(u, b, a);
+        let d = self.point(u, j + 2);
+        in_circle(self.point(t, 0), self.point(t, 1), self.point(t, 2), d) <= 0.0
+    }
+
+    // 插入点 v 后恢复 Delaunay 性质，栈中为 v 对面的边
+    fn legalize(&mut self, mut stack: Vec<(usize, usize)>, v: usize) {
+        while let Some((t, i)) = stack.pop() {
+            if self.triangles[t][(i + 2) % 3] != v || self.is_delaunay(t, i) {
+                continue;
+            }
+            let (t0, t1) = self.flip(t, i);
+            // 翻转后 t0 = (v, a, d)，u = (d, b, v)
+            stack.push((t0, 1));
+            stack.push((t1, 0));
+        }
+    }
+
+    // 顶点周围的三角形及顶点在其中的位置
+    fn triangles_around(&self, v: usize) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        let start = self.vertex_triangle[v];
+        let mut t = start;
+        loop {
+            let k = (0..3).find(|&k| self.triangles[t][k] == v).unwrap();
+            result.push((t, k));
+            t = self.neighbours[t][(k + 2) % 3];
+            if t == start {
+                return result;
+            }
+            if t == NONE {
+                break;
+            }
+        }
+        // 碰到凸包时反向旋转
+        let mut t = start;
+        loop {
+            let k = (0..3).find(|&k| self.triangles[t][k] == v).unwrap();
+            t = self.neighbours[t][k];
+            if t == NONE {
+                return result;
+            }
+            let k = (0..3).find(|&k| self.triangles[t][k] == v).unwrap();
+            result.push((t, k));
+        }
+    }
+
+    fn find_edge(&self, a: usize, b: usize) -> Option<(usize, usize)> {
+        for (t, k) in self.triangles_around(a) {
+            if self.triangles[t][(k + 1) % 3] == b {
+                return Some((t, k));
+            }
+        }
+        None
+    }
+
+    fn mark_constraint(&mut self, a: usize, b: usize, kind: u8) {
+        for (p, q) in [(a, b), (b, a)] {
+            if let Some((t, i)) = self.find_edge(p, q) {
+                self.constraints[t][i] = self.constraints[t][i].max(kind);
+            }
+        }
+    }
+
+    // a、b 之间严格位于线段上的点
+    fn is_on_segment(&self, a: usize, b: usize, v: usize) -> bool {
+        let (pa, pb, pv) = (self.points[a], self.points[b], self.points[v]);
+        let dot = (pv[0] - pa[0]) * (pb[0] - pa[0]) + (pv[1] - pa[1]) * (pb[1] - pa[1]);
+        self.side(pa, pb, pv).abs() <= self.tolerance && dot > 0.0 && dot < distance2(pa, pb)
+    }
+
+    // 插入约束边，返回是否成功；线段经过已有点或与约束边相交时分段插入，任一段失败即为失败
+    fn insert_segment(&mut self, a: usize, b: usize, kind: u8) -> bool {
+        if a == b {
+            return true;
+        }
+        if self.has_edge(a, b) {
+            self.mark_constraint(a, b, kind);
+            return true;
+        }
+
+        // 找到 a 周围被线段穿过的三角形
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let mut first = None;
+        for (t, k) in self.triangles_around(a) {
+            let p = self.triangles[t][(k + 1) % 3];
+            let q = self.triangles[t][(k + 2) % 3];
+            for v in [p, q] {
+                if self.is_on_segment(a, b, v) {
+                    let first = self.insert_segment(a, v, kind);
+                    return self.insert_segment(v, b, kind) && first;
+                }
+            }
+            if orient(pa, self.points[p], pb) > 0.0 && orient(pa, self.points[q], pb) < 0.0 {
+                first = Some((t, p, q));
+            }
+        }
+        let (mut t, mut p, mut q) = match first {
+            Some(first) => first,
+            None => return false,
+        };
+
+        // 沿线段收集穿过的边
+        let mut crossing: VecDeque<(usize, usize)> = VecDeque::new();
+        loop {
+            let i = self.edge_index(t, p, q);
+            if self.constraints[t][i] != FREE {
+                // 与已有约束边相交，插入交点后重新处理
+                let (pp, pq) = (self.points[p], self.points[q]);
+                let s = orient(pa, pb, pp) / (orient(pa, pb, pp) - orient(pa, pb, pq));
+                let x = [
+                    pp[0] + (pq[0] - pp[0]) * s,
+                    pp[1] + (pq[1] - pp[1]) * s,
+                    pp[2] + (pq[2] - pp[2]) * s,
+                ];
+                self.split_edge(t, i, x);
+                return self.insert_segment(a, b, kind);
+            }
+            crossing.push_back((p, q));
+            let u = self.neighbours[t][i];
+            let j = self.edge_index(u, q, p);
+            let w = self.triangles[u][(j + 2) % 3];
+            if w == b {
+                break;
+            }
+            if self.is_on_segment(a, b, w) {
+                let first = self.insert_segment(a, w, kind);
+                return self.insert_segment(w, b, kind) && first;
+            }
+            // p 在线段右侧，q 在左侧
+            if orient(pa, pb, self.points[w]) > 0.0 {
+                q = w;
+            } else {
+                p = w;
+            }
+            t = u;
+        }
+
+        // 翻转穿过的边直到线段成为三角网的边
+        let mut new_edges: Vec<(usize, usize)> = Vec::new();
+        let mut guard = 0;
+        while let Some((p, q)) = crossing.pop_front() {
+            guard += 1;
+            if guard > 100 * (self.triangles.len() + 10) {
+                break;
+            }
+            let (t, i) = match self.find_edge(p, q) {
+                Some(edge) => edge,
+                None => continue,
+            };
+            let u = self.neighbours[t][i];
+            let c = self.point(t, i + 2);
+            let j = self.edge_index(u, q, p);
+            let d = self.point(u, j + 2);
+            // 四边形非凸时暂不翻转
+            if orient(c, d, self.points[p]) * orient(c, d, self.points[q]) >= 0.0 {
+                crossing.push_back((p, q));
+                continue;
+            }
+            let (t0, _) = self.flip(t, i);
+            let c = self.triangles[t0][0];
+            let d = self.triangles[t0][2];
+            let (pc, pd) = (self.points[c], self.points[d]);
+            let crosses = c != a
+                && c != b
+                && d != a
+                && d != b
+                && orient(pa, pb, pc) * orient(pa, pb, pd) < 0.0
+                && orient(pc, pd, pa) * orient(pc, pd, pb) < 0.0;
+            if crosses {
+                crossing.push_back((c, d));
+            } else {
+                new_edges.push((c, d));
+            }
+        }
+        if !self.has_edge(a, b) {
+            return false;
+        }
+        self.mark_constraint(a, b, kind);
+
+        // 恢复新边的 Delaunay 性质
+        let mut changed = true;
+        let mut guard = 0;
+        while changed && guard < 100 {
+            changed = false;
+            guard += 1;
+            for edge in new_edges.iter_mut() {
+                if let Some((t, i)) = self.find_edge(edge.0, edge.1) {
+                    if !self.is_delaunay(t, i) {
+                        let (t0, _) = self.flip(t, i);
+                        *edge = (self.triangles[t0][0], self.triangles[t0][2]);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.find_edge(a, b).is_some() || self.find_edge(b, a).is_some()
+    }
+
+    // 从超级三角形出发向内扩散，每越过一条边界边内外互换，孔洞内及孔洞中的岛按层次交替
+    fn mark_inside(&mut self) {
+        let len = self.triangles.len();
+        self.inside = vec![false; len];
+        let mut visited = vec![false; len];
+        let mut queue: VecDeque<usize> = (0..len)
+            .filter(|&t| self.triangles[t].iter().any(|&v| v < 3))
+            .collect();
+        for &t in &queue {
+            visited[t] = true;
+        }
+        while let Some(t) = queue.pop_front() {
+            for i in 0..3 {
+                let n = self.neighbours[t][i];
+                if n != NONE && !visited[n] {
+                    visited[n] = true;
+                    self.inside[n] = self.inside[t] != (self.constraints[t][i] == BOUNDARY);
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    fn is_bad(&self, t: usize, min_angle: f64, max_area: f64) -> bool {
+        let [a, b, c] = [self.point(t, 0), self.point(t, 1), self.point(t, 2)];
+        let area = orient(a, b, c) / 2.0;
+        if area > max_area {
+            return true;
+        }
+        let (la, lb, lc) = (distance2(b, c), distance2(c, a), distance2(a, b));
+        // 最短边所对的角最小
+        let shortest = la.min(lb).min(lc);
+        if shortest.sqrt() < self.tolerance * 1e4 {
+            return false;
+        }
+        let longest = la.max(lb).max(lc);
+        let sin_min = 2.0 * area / (longest.sqrt() * (la + lb + lc - shortest - longest).sqrt());
+        sin_min.clamp(-1.0, 1.0).asin().to_degrees() < min_angle
+    }
+
+    fn circumcenter(&self, t: usize) -> [f64; 3] {
+        let [a, b, c] = [self.point(t, 0), self.point(t, 1), self.point(t, 2)];
+        let (bx, by) = (b[0] - a[0], b[1] - a[1]);
+        let (cx, cy) = (c[0] - a[0], c[1] - a[1]);
+        let d = 2.0 * (bx * cy - by * cx);
+        let b2 = bx * bx + by * by;
+        let c2 = cx * cx + cy * cy;
+        [
+            a[0] + (cy * b2 - by * c2) / d,
+            a[1] + (bx * c2 - cx * b2) / d,
+            0.0,
+        ]
+    }
+
+    // 线性插值三角形内点的 z
+    fn interpolate_z(&self, t: usize, p: [f64; 3]) -> f64 {
+        let [a, b, c] = [self.point(t, 0), self.point(t, 1), self.point(t, 2)];
+        let area = orient(a, b, c);
+        if area == 0.0 {
+            return (a[2] + b[2] + c[2]) / 3.0;
+        }
+        let wa = orient(b, c, p) / area;
+        let wb = orient(c, a, p) / area;
+        let wc = 1.0 - wa - wb;
+        wa * a[2] + wb * b[2] + wc * c[2]
+    }
+
+    fn split_segment(&mut self, t: usize, i: usize) -> bool {
+        let (pa, pb) = (self.point(t, i), self.point(t, i + 1));
+        if distance2(pa, pb).sqrt() < self.tolerance * 1e4 {
+            return false;
+        }
+        let m = [
+            (pa[0] + pb[0]) / 2.0,
+            (pa[1] + pb[1]) / 2.0,
+            (pa[2] + pb[2]) / 2.0,
+        ];
+        self.split_edge(t, i, m);
+        true
+    }
+
+    // 插入外心；外心侵入约束边时改为平分该约束边
+    fn refine_triangle(&mut self, t: usize) -> bool {
+        let mut c = self.circumcenter(t);
+        let [a0, a1, a2] = [self.point(t, 0), self.point(t, 1), self.point(t, 2)];
+        let g = [
+            (a0[0] + a1[0] + a2[0]) / 3.0,
+            (a0[1] + a1[1] + a2[1]) / 3.0,
+            0.0,
+        ];
+
+        let mut current = t;
+        let mut step = 0;
+        loop {
+            step += 1;
+            if step > self.triangles.len() {
+                return false;
+            }
+            let mut exit = None;
+            for i in 0..3 {
+                let (p, q) = (self.point(current, i), self.point(current, i + 1));
+                if orient(p, q, c) < 0.0 && orient(g, c, p) * orient(g, c, q) <= 0.0 {
+                    exit = Some(i);
+                    break;
+                }
+            }
+            match exit {
+                Some(i) => {
+                    if self.constraints[current][i] != FREE {
+                        return self.split_segment(current, i);
+                    }
+                    let n = self.neighbours[current][i];
+                    if n == NONE || !self.inside[n] {
+                        return false;
+                    }
+                    current = n;
+                }
+                None => break,
+            }
+        }
+
+        for i in 0..3 {
+            if self.constraints[current][i] != FREE {
+                let (p, q) = (self.point(current, i), self.point(current, i + 1));
+                let m = [(p[0] + q[0]) / 2.0, (p[1] + q[1]) / 2.0, 0.0];
+                if distance2(m, c) < distance2(p, q) / 4.0 {
+                    return self.split_segment(current, i);
+                }
+            }
+        }
+        c[2] = self.interpolate_z(current, c);
+        self.last = current;
+        let before = self.points.len();
+        self.insert_point(c);
+        self.points.len() > before
+    }
+
+    fn refine(&mut self, options: &TriangulateOptions) {
+        let min_angle = options.min_angle.unwrap_or(0.0).min(MAX_MIN_ANGLE);
+        let max_area = options.max_area.unwrap_or(f64::MAX);
+        if min_angle <= 0.0 && max_area == f64::MAX {
+            return;
+        }
+        let mut inserted = 0;
+        loop {
+            let bad: Vec<usize> = (0..self.triangles.len())
+                .filter(|&t| self.inside[t] && self.is_bad(t, min_angle, max_area))
+                .collect();
+            if bad.is_empty() {
+                return;
+            }
+            let mut progress = false;
+            for t in bad {
+                if inserted >= options.max_steiner_points {
+                    return;
+                }
+                if self.inside[t] && self.is_bad(t, min_angle, max_area) && self.refine_triangle(t)
+                {
+                    inserted += 1;
+                    progress = true;
+                }
+            }
+            if !progress {
+                return;
+            }
+        }
+    }
+}
+
+impl MeshCoverage {
+    // 由散点、断裂线及可选的边界多边形、孔洞生成约束 Delaunay 三角网
+    // 未给出边界时以所有点的凸包为边界；未能插入的点及约束线段列在报告中
+    pub fn triangulate(
+        id: String,
+        points: &[[f64; 3]],
+        breaklines: &[Vec<[f64; 3]>],
+        boundary: Option<&[[f64; 3]]>,
+        holes: &[Vec<[f64; 3]>],
+        options: &TriangulateOptions,
+    ) -> (MeshCoverage, TriangulateReport) {
+        let mut coverage = MeshCoverage::new(id);
+        let mut report = TriangulateReport::default();
+
+        let mut all: Vec<[f64; 3]> = points.to_vec();
+        for line in breaklines.iter().chain(holes) {
+            all.extend(line.iter().copied());
+        }
+        if let Some(polygon) = boundary {
+            all.extend(polygon.iter().copied());
+        }
+        if all.len() < 3 {
+            return (coverage, report);
+        }
+
+        // 平移到局部坐标，减小大坐标带来的误差
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for p in &all {
+            min_x = min_x.min(p[0]);
+            max_x = max_x.max(p[0]);
+            min_y = min_y.min(p[1]);
+            max_y = max_y.max(p[1]);
+        }
+        let (c_x, c_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+        let local = |p: &[f64; 3]| [p[0] - c_x, p[1] - c_y, p[2]];
+        let extent = (max_x - min_x).max(max_y - min_y);
+
+        let mut triangulation = Triangulation::new(extent);
+        for p in points {
+            if triangulation.insert_point(local(p)).is_none() {
+                report.missing_points.push(*p);
+            }
+        }
+        let mut insert_polyline = |line: &[[f64; 3]], closed: bool, kind: u8| {
+            let mut ids: Vec<(usize, [f64; 3])> = Vec::new();
+            for p in line {
+                match triangulation.insert_point(local(p)) {
+                    Some(id) => ids.push((id, *p)),
+                    None => report.missing_points.push(*p),
+                }
+            }
+            let mut segments: Vec<(usize, usize)> = (1..ids.len()).map(|i| (i - 1, i)).collect();
+            if closed && ids.len() > 2 {
+                segments.push((ids.len() - 1, 0));
+            }
+            for (i, j) in segments {
+                if !triangulation.insert_segment(ids[i].0, ids[j].0, kind) {
+                    let missing = if kind == BOUNDARY {
+                        &mut report.missing_boundaries
+                    } else {
+                        &mut report.missing_breaklines
+                    };
+                    missing.push((ids[i].1, ids[j].1));
+                }
+            }
+        };
+        for line in breaklines {
+            insert_polyline(line, false, BREAKLINE);
+        }
+        match boundary {
+            Some(polygon) => insert_polyline(polygon, true, BOUNDARY),
+            None => insert_polyline(&convex_hull(&all), true, BOUNDARY),
+        }
+        for hole in holes {
+            insert_polyline(hole, true, BOUNDARY);
+        }
+
+        triangulation.mark_inside();
+        triangulation.refine(options);
+
+        let mut node_ids: Vec<u32> = vec![0; triangulation.points.len()];
+        for t in 0..triangulation.triangles.len() {
+            if !triangulation.inside[t] {
+                continue;
+            }
+            let face = triangulation.triangles[t].map(|v| {
+                if node_ids[v] == 0 {
+                    let p = triangulation.points[v];
+                    node_ids[v] = coverage.create_node(p[0] + c_x, p[1] + c_y, p[2]);
+                }
+                node_ids[v]
+            });
+            coverage.create_face(face[0], face[1], face[2], 0);
+        }
+        coverage.generate_half_edges();
+        (coverage, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::geometry::signed_area;
+    use super::*;
+
+    fn square(min: f64, max: f64) -> Vec<[f64; 3]> {
+        vec![
+            [min, min, 0.0],
+            [max, min, 0.0],
+            [max, max, 0.0],
+            [min, max, 0.0],
+        ]
+    }
+
+    fn grid_points(n: usize, size: f64) -> Vec<[f64; 3]> {
+        let mut points = Vec::new();
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, y) = (i as f64 * size / n as f64, j as f64 * size / n as f64);
+                points.push([x, y, x + y]);
+            }
+        }
+        points
+    }
+
+    fn face_areas(coverage: &MeshCoverage) -> Vec<(f64, (f64, f64))> {
+        coverage
+            .face_map
+            .values()
+            .map(|face| {
+                let points = coverage.get_face_points(&face.node_ids());
+                let n = points.len() as f64;
+                let centroid = (
+                    points.iter().map(|p| p.0).sum::<f64>() / n,
+                    points.iter().map(|p| p.1).sum::<f64>() / n,
+                );
+                (signed_area(&points), centroid)
+            })
+            .collect()
+    }
+
+    fn inside(p: (f64, f64), min: f64, max: f64) -> bool {
+        p.0 > min && p.0 < max && p.1 > min && p.1 < max
+    }
+
+    #[test]
+    fn boundary_with_hole() {
+        let (coverage, report) = MeshCoverage::triangulate(
+            "a".into(),
+            &grid_points(10, 10.0),
+            &[],
+            Some(&square(0.0, 10.0)),
+            &[square(3.5, 6.5)],
+            &TriangulateOptions::default(),
+        );
+        assert!(report.is_empty(), "{}", report);
+        assert!(coverage.validate().is_valid());
+        let areas = face_areas(&coverage);
+        let total: f64 = areas.iter().map(|(area, _)| area).sum();
+        assert!((total - (100.0 - 9.0)).abs() < 1e-9, "area {}", total);
+        for (area, centroid) in areas {
+            assert!(area > 0.0);
+            assert!(
+                !inside(centroid, 3.5, 6.5),
+                "face in hole at {:?}",
+                centroid
+            );
+        }
+        let boundary = coverage.get_boundary();
+        assert_eq!(boundary.outer_loops.len(), 1);
+        assert_eq!(boundary.holes.len(), 1);
+    }
+
+    #[test]
+    fn island_in_hole() {
+        // 孔洞中的第二个环按层次交替，成为岛
+        let (coverage, report) = MeshCoverage::triangulate(
+            "a".into(),
+            &[],
+            &[],
+            Some(&square(0.0, 10.0)),
+            &[square(2.0, 8.0), square(4.0, 6.0)],
+            &TriangulateOptions::default(),
+        );
+        assert!(report.is_empty(), "{}", report);
+        let areas = face_areas(&coverage);
+        let total: f64 = areas.iter().map(|(area, _)| area).sum();
+        assert!(
+            (total - (100.0 - 36.0 + 4.0)).abs() < 1e-9,
+            "area {}",
+            total
+        );
+        for (_, centroid) in areas {
+            assert!(!inside(centroid, 2.0, 8.0) || inside(centroid, 4.0, 6.0));
+        }
+        assert_eq!(coverage.get_boundary().outer_loops.len(), 2);
+    }
+
+    #[test]
+    fn refined_hole_keeps_constraints() {
+        let options = TriangulateOptions {
+            min_angle: Some(25.0),
+            max_area: Some(0.5),
+            ..Default::default()
+        };
+        let (coverage, report) = MeshCoverage::triangulate(
+            "a".into(),
+            &[],
+            &[vec![[1.0, 1.0, 0.0], [9.0, 9.0, 0.0]]],
+            Some(&square(0.0, 10.0)),
+            &[square(4.0, 6.0)],
+            &options,
+        );
+        assert!(report.is_empty(), "{}", report);
+        assert!(coverage.validate().is_valid());
+        let areas = face_areas(&coverage);
+        let total: f64 = areas.iter().map(|(area, _)| area).sum();
+        assert!((total - 96.0).abs() < 1e-9, "area {}", total);
+        for (area, centroid) in areas {
+            assert!(area <= 0.5 + 1e-9);
+            assert!(!inside(centroid, 4.0, 6.0));
+        }
+    }
+
+    #[test]
+    fn breakline_is_not_crossed() {
+        let line = [[0.5, 2.3, 0.0], [9.5, 7.1, 0.0]];
+        let (coverage, report) = MeshCoverage::triangulate(
+            "a".into(),
+            &grid_points(10, 10.0),
+            &[line.to_vec()],
+            None,
+            &[],
+            &TriangulateOptions::default(),
+        );
+        assert!(report.is_empty(), "{}", report);
+        let (a, b) = (line[0], line[1]);
+        for face in coverage.face_map.values() {
+            let ids = face.node_ids();
+            for i in 0..ids.len() {
+                let (p, q) = (
+                    coverage.node_map[&ids[i]],
+                    coverage.node_map[&ids[(i + 1) % ids.len()]],
+                );
+                let (p, q) = ([p.x, p.y, 0.0], [q.x, q.y, 0.0]);
+                let crosses = orient(a, b, p) * orient(a, b, q) < -1e-12
+                    && orient(p, q, a) * orient(p, q, b) < -1e-12;
+                assert!(!crosses, "edge {:?} {:?} crosses the breakline", p, q);
+            }
+        }
+    }
+
+    #[test]
+    fn report_lists_problems() {
+        let mut report = TriangulateReport::default();
+        assert_eq!(report.to_string(), "all constraints inserted.");
+        report
+            .missing_boundaries
+            .push(([0.0, 0.0, 0.0], [1.0, 0.0, 0.0]));
+        assert!(!report.is_empty());
+        assert!(report.to_string().contains("missing boundary segments: 1"));
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+
+use super::{
+    geometry::{is_same_winding, signed_area},
+    MeshCoverage,
+};
+
+impl MeshCoverage {
+    pub fn get_face_points(&self, node_ids: &[u32]) -> Vec<(f64, f64)> {
+        node_ids
+            .iter()
+            .map(|id| {
+                let node = self.node_map[id];
+                (node.x, node.y)
+            })
+            .collect()
+    }
+
+    // 与节点共边的相邻节点
+    pub fn get_node_neighbours(&self, node_id: u32) -> HashSet<u32> {
+        let mut result = HashSet::new();
+        if let Some(face_set) = self.node_face_adj.get_node_adj_faces(node_id) {
+            for face_id in face_set {
+                let ids = self.face_map[face_id].node_ids();
+                let len = ids.len();
+                for i in 0..len {
+                    if ids[i] == node_id {
+                        result.insert(ids[(i + 1) % len]);
+                        result.insert(ids[(i + len - 1) % len]);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn is_boundary_node(&self, node_id: u32) -> bool {
+        self.find_boundary_half_edge_from(node_id).is_some()
+    }
+
+    // 翻转两个三角形的公共边，返回新建的两个面
+    pub fn flip_edge(&mut self, half_edge_id: u32) -> Option<(u32, u32)> {
+        let half_edge = *self.half_edge_map.get(&half_edge_id)?;
+        let twin = *self.half_edge_map.get(&half_edge.twin_id)?;
+        if half_edge.face_id == 0 || twin.face_id == 0 {
+            return None;
+        }
+        let face = self.face_map[&half_edge.face_id];
+        let twin_face = self.face_map[&twin.face_id];
+        if !face.is_triangle() || !twin_face.is_triangle() {
+            return None;
+        }
+
+        let a = half_edge.start_id;
+        let b = half_edge.end_id;
+        let c = self.half_edge_map[&half_edge.next_id].end_id;
+        let d = self.half_edge_map[&twin.next_id].end_id;
+        if c == d || self.get_node_neighbours(c).contains(&d) {
+            return None;
+        }
+
+        // 四边形非凸时翻转会产生反向三角形
+        let sign = signed_area(&self.get_face_points(&[a, b, c])).signum();
+        if !is_same_winding(&self.get_face_points(&[a, d, c]), sign)
+            || !is_same_winding(&self.get_face_points(&[d, b, c]), sign)
+        {
+            return None;
+        }
+
+        // 新面的数据集值取原来两个面的平均值
+        let weights = [(half_edge.face_id, 0.5), (twin.face_id, 0.5)];
+        let values = self.get_face_values(&[half_edge.face_id, twin.face_id]);
+        self.remove_face(half_edge.face_id);
+        self.remove_face(twin.face_id);
+        let face_0 = self.add_face(a, d, c, 0);
+        let face_1 = self.add_face(d, b, c, 0);
+        self.interpolate_face_values(&values, face_0, &weights);
+        self.interpolate_face_values(&values, face_1, &weights);
+        Some((face_0, face_1))
+    }
+
+    // 在边上参数 t 处插入节点，并将两侧的面以新节点为中心重新三角化
+    pub fn split_edge(&mut self, half_edge_id: u32, t: f64) -> Option<u32> {
+        if t <= 0.0 || t >= 1.0 {
+            return None;
+        }
+        let half_edge = *self.half_edge_map.get(&half_edge_id)?;
+        let start = self.node_map[&half_edge.start_id];
+        let end = self.node_map[&half_edge.end_id];
+
+        let x = start.x + (end.x - start.x) * t;
+        let y = start.y + (end.y - start.y) * t;
+
+        // 以新节点为扇形中心，依次连接面上除该边以外的各条边
+        let mut old_faces: Vec<u32> = Vec::new();
+        let mut new_faces: Vec<(u32, [u32; 2])> = Vec::new();
+        for id in [half_edge_id, half_edge.twin_id] {
+            if let Some(side) = self.half_edge_map.get(&id) {
+                if side.face_id == 0 {
+                    continue;
+                }
+                let node_ids = self.face_map[&side.face_id].node_ids();
+                let sign = signed_area(&self.get_face_points(&node_ids)).signum();
+                let len = node_ids.len();
+                let i = node_ids.iter().position(|&id| id == side.start_id)?;
+                for j in 1..len {
+                    let pair = [node_ids[(i + j) % len], node_ids[(i + j + 1) % len]];
+                    let mut points = self.get_face_points(&pair);
+                    points.insert(0, (x, y));
+                    if !is_same_winding(&points, sign) {
+                        return None;
+                    }
+                    new_faces.push((side.face_id, pair));
+                }
+                old_faces.push(side.face_id);
+            }
+        }
+
+        let new_id = self.create_node(x, y, start.z + (end.z - start.z) * t);
+        self.interpolate_node_values(
+            new_id,
+            &[(half_edge.start_id, 1.0 - t), (half_edge.end_id, t)],
+        );
+        let values = self.get_face_values(&old_faces);
+        for face_id in old_faces {
+            self.remove_face(face_id);
+        }
+        for (face_id, [n1, n2]) in new_faces {
+            let id = self.add_face(new_id, n1, n2, 0);
+            self.interpolate_face_values(&values, id, &[(face_id, 1.0)]);
+        }
+        Some(new_id)
+    }
+
+    // 收缩边，保留的节点移动到边的中点；只有一端在边界上时保留边界节点的位置
+    pub fn collapse_edge(&mut self, half_edge_id: u32) -> Option<u32> {
+        let half_edge = *self.half_edge_map.get(&half_edge_id)?;
+        let start = self.node_map[&half_edge.start_id];
+        let end = self.node_map[&half_edge.end_id];
+        let start_boundary = self.is_boundary_node(half_edge.start_id);
+        let end_boundary = self.is_boundary_node(half_edge.end_id);
+        if start_boundary && !end_boundary {
+            self.collapse_edge_to(half_edge_id, start.x, start.y, start.z)
+        } else if !start_boundary && end_boundary {
+            self.collapse_edge_to(half_edge.twin_id, end.x, end.y, end.z)
+        } else {
+            self.collapse_edge_to(
+                half_edge_id,
+                (start.x + end.x) / 2.0,
+                (start.y + end.y) / 2.0,
+                (start.z + end.z) / 2.0,
+            )
+        }
+    }
+
+    // 将边的终点合并到起点，并把起点移动到 (x, y, z)；会破坏拓扑或使面翻转时不做修改
+    pub fn collapse_edge_to(&mut self, half_edge_id: u32, x: f64, y: f64, z: f64) -> Option<u32> {
+        let half_edge = *self.half_edge_map.get(&half_edge_id)?;
+        let twin = *self.half_edge_map.get(&half_edge.twin_id)?;
+        let keep_id = half_edge.start_id;
+        let remove_id = half_edge.end_id;
+
+        // 连接条件：两端共同的相邻节点只能是边两侧三角形的对顶点
+        let mut opposite: HashSet<u32> = HashSet::new();
+        for side in [half_edge, twin] {
+            if side.face_id > 0 && self.face_map[&side.face_id].is_triangle() {
+                opposite.insert(self.half_edge_map[&side.next_id].end_id);
+            }
+        }
+        let common: HashSet<u32> = self
+            .get_node_neighbours(keep_id)
+            .intersection(&self.get_node_neighbours(remove_id))
+            .copied()
+            .collect();
+        if common != opposite {
+            return None;
+        }
+        let edge_boundary = half_edge.face_id == 0 || twin.face_id == 0;
+        if !edge_boundary && self.is_boundary_node(keep_id) && self.is_boundary_node(remove_id) {
+            return None;
+        }
+
+        let mut face_ids: Vec<u32> = Vec::new();
+        for node_id in [keep_id, remove_id] {
+            if let Some(set) = self.node_face_adj.get_node_adj_faces(node_id) {
+                face_ids.extend(set.iter().copied());
+            }
+        }
+        face_ids.sort();
+        face_ids.dedup();
+
+        let mut new_faces: Vec<(u32, Vec<u32>)> = Vec::new();
+        for &face_id in &face_ids {
+            let old_ids = self.face_map[&face_id].node_ids();
+            let mut ids: Vec<u32> = Vec::new();
+            for &id in &old_ids {
+                let id = if id == remove_id { keep_id } else { id };
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+            if ids.len() > 1 && ids.first() == ids.last() {
+                ids.pop();
+            }
+            if ids.len() < 3 {
+                continue;
+            }
+            let unique: HashSet<u32> = ids.iter().copied().collect();
+            if unique.len() < ids.len() {
+                return None;
+            }
+
+            let sign = signed_area(&self.get_face_points(&old_ids)).signum();
+            let points: Vec<(f64, f64)> = ids
+                .iter()
+                .map(|id| {
+                    if *id == keep_id {
+                        (x, y)
+                    } else {
+                        let node = self.node_map[id];
+                        (node.x, node.y)
+                    }
+                })
+                .collect();
+            if !is_same_winding(&points, sign) {
+                return None;
+            }
+            new_faces.push((face_id, ids));
+        }
+
+        let values = self.get_face_values(&face_ids);
+        for face_id in face_ids {
+            self.remove_face(face_id);
+        }
+        self.remove_node(remove_id);
+        self.set_node_position(keep_id, x, y, z);
+        for (face_id, ids) in new_faces {
+            let new_id = self.add_face(
+                ids[0],
+                ids[1],
+                ids[2],
+                if ids.len() > 3 { ids[3] } else { 0 },
+            );
+            self.interpolate_face_values(&values, new_id, &[(face_id, 1.0)]);
+        }
+        Some(keep_id)
+    }
+}
@@ -0,0 +1,87 @@
+// 面积与最长边平方之比小于该值时视为退化
+const ZERO_AREA_RATIO: f64 = 1e-12;
+
+pub fn signed_area(points: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+    let len = points.len();
+    for i in 0..len {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % len];
+        area += x0 * y1 - x1 * y0;
+    }
+    area / 2.0
+}
+
+fn max_edge_len2(points: &[(f64, f64)]) -> f64 {
+    let mut max_len2: f64 = 0.0;
+    let len = points.len();
+    for i in 0..len {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % len];
+        max_len2 = max_len2.max((x1 - x0).powi(2) + (y1 - y0).powi(2));
+    }
+    max_len2
+}
+
+// 面积与 sign 同号且不退化
+pub fn is_same_winding(points: &[(f64, f64)], sign: f64) -> bool {
+    signed_area(points) * sign > max_edge_len2(points) * ZERO_AREA_RATIO
+}
+
+// 两种绕向都不成立，即面积与最长边平方之比接近零
+pub fn is_zero_area(points: &[(f64, f64)]) -> bool {
+    signed_area(points).abs() <= max_edge_len2(points) * ZERO_AREA_RATIO
+}
+
+// 多边形各内角，单位为度；按 points 的绕向计算，凹角大于 180
+pub fn interior_angles(points: &[(f64, f64)]) -> Vec<f64> {
+    let sign = signed_area(points).signum();
+    let len = points.len();
+    let mut angles = Vec::with_capacity(len);
+    for i in 0..len {
+        let (x0, y0) = points[(i + len - 1) % len];
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i + 1) % len];
+        let (ax, ay) = (x0 - x1, y0 - y1);
+        let (bx, by) = (x2 - x1, y2 - y1);
+        let cross = bx * ay - by * ax;
+        let dot = ax * bx + ay * by;
+        let mut angle = (cross * sign).atan2(dot).to_degrees();
+        if angle < 0.0 {
+            angle += 360.0;
+        }
+        angles.push(angle);
+    }
+    angles
+}
+
+// 射线法判断点是否在多边形内，多边形首尾点不重复
+pub fn point_in_polygon(point: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    let (x, y) = point;
+    let len = polygon.len();
+    let mut inside = false;
+    for i in 0..len {
+        let (x0, y0) = polygon[i];
+        let (x1, y1) = polygon[(i + 1) % len];
+        if (y0 > y) != (y1 > y) && x < x0 + (y - y0) * (x1 - x0) / (y1 - y0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// 点在线段 ab 上的投影参数，限制在 0~1
+pub fn project_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    if len2 == 0.0 {
+        return 0.0;
+    }
+    (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+}
+
+pub fn point_segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let t = project_to_segment(p, a, b);
+    let (x, y) = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
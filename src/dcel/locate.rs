@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use super::{spatial::Rect, MeshCoverage};
+
+// 权重的容差，点在边上时允许略小于 0
+const WEIGHT_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointLocation {
+    pub face_id: u32,
+    // 面上各节点及其权重，三角形为重心坐标，四边形为双线性权重，权重之和为 1
+    pub weights: Vec<(u32, f64)>,
+}
+
+impl PointLocation {
+    // 按权重插值节点上的值，缺少值的节点返回 None
+    pub fn interpolate<F: Fn(u32) -> Option<f64>>(&self, value: F) -> Option<f64> {
+        let mut result = 0.0;
+        for &(node_id, weight) in &self.weights {
+            result += value(node_id)? * weight;
+        }
+        Some(result)
+    }
+}
+
+impl MeshCoverage {
+    // 查找包含 (x, y) 的面并计算插值权重；点在公共边上时返回 id 较小的面
+    pub fn locate_point(&self, x: f64, y: f64) -> Option<PointLocation> {
+        let mut face_ids: Vec<u32> = self.face_index.query_rect(&Rect::point(x, y));
+        face_ids.sort();
+        face_ids
+            .into_iter()
+            .find_map(|face_id| self.get_face_weights(face_id, x, y))
+    }
+
+    // 点在面内时返回插值权重
+    pub fn get_face_weights(&self, face_id: u32, x: f64, y: f64) -> Option<PointLocation> {
+        let ids = self.face_map.get(&face_id)?.node_ids();
+        let weights = self.get_weights(&ids, x, y)?;
+        Some(PointLocation {
+            face_id,
+            weights: ids.into_iter().zip(weights).collect(),
+        })
+    }
+
+    // 由三个或四个节点围成的面（不必已加入网格）上的插值权重，点不在面内时为 None
+    pub fn get_weights(&self, node_ids: &[u32], x: f64, y: f64) -> Option<Vec<f64>> {
+        if !node_ids.iter().all(|id| self.node_map.contains_key(id)) {
+            return None;
+        }
+        let points = self.get_face_points(node_ids);
+        match points.len() {
+            3 => barycentric(&points, (x, y)),
+            4 => bilinear(&points, (x, y)),
+            _ => None,
+        }
+    }
+
+    pub fn interpolate_z_at(&self, x: f64, y: f64) -> Option<f64> {
+        self.locate_point(x, y)?
+            .interpolate(|id| self.node_map.get(&id).map(|node| node.z))
+    }
+
+    // 插值任意节点值，如水深、流速
+    pub fn interpolate_value_at(&self, x: f64, y: f64, values: &HashMap<u32, f64>) -> Option<f64> {
+        self.locate_point(x, y)?
+            .interpolate(|id| values.get(&id).copied())
+    }
+}
+
+fn barycentric(points: &[(f64, f64)], p: (f64, f64)) -> Option<Vec<f64>> {
+    let (a, b, c) = (points[0], points[1], points[2]);
+    let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    if area == 0.0 {
+        return None;
+    }
+    let wa = ((b.0 - p.0) * (c.1 - p.1) - (b.1 - p.1) * (c.0 - p.0)) / area;
+    let wb = ((c.0 - p.0) * (a.1 - p.1) - (c.1 - p.1) * (a.0 - p.0)) / area;
+    let wc = 1.0 - wa - wb;
+    if wa < -WEIGHT_EPSILON || wb < -WEIGHT_EPSILON || wc < -WEIGHT_EPSILON {
+        return None;
+    }
+    Some(vec![wa, wb, wc])
+}
+
+fn cross(a: (f64, f64), b: (f64, f64)) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+// 反算双线性参数 (u, v)：p = (1-u)(1-v)a + u(1-v)b + uv·c + (1-u)v·d
+fn bilinear(points: &[(f64, f64)], p: (f64, f64)) -> Option<Vec<f64>> {
+    let (a, b, c, d) = (points[0], points[1], points[2], points[3]);
+    let e = (b.0 - a.0, b.1 - a.1);
+    let f = (d.0 - a.0, d.1 - a.1);
+    let g = (a.0 - b.0 + c.0 - d.0, a.1 - b.1 + c.1 - d.1);
+    let h = (p.0 - a.0, p.1 - a.1);
+
+    let k2 = cross(g, f);
+    let k1 = cross(e, f) + cross(h, g);
+    let k0 = cross(h, e);
+
+    let scale = cross(e, f).abs().max(f64::MIN_POSITIVE);
+    let mut candidates: Vec<f64> = Vec::new();
+    if k2.abs() <= scale * 1e-12 {
+        if k1 == 0.0 {
+            return None;
+        }
+        candidates.push(-k0 / k1);
+    } else {
+        let w = k1 * k1 - 4.0 * k0 * k2;
+        if w < 0.0 {
+            return None;
+        }
+        let w = w.sqrt();
+        candidates.push((-k1 - w) / (2.0 * k2));
+        candidates.push((-k1 + w) / (2.0 * k2));
+    }
+
+    let range = -WEIGHT_EPSILON..=1.0 + WEIGHT_EPSILON;
+    for v in candidates {
+        if !range.contains(&v) {
+            continue;
+        }
+        let denominator = (e.0 + g.0 * v, e.1 + g.1 * v);
+        let u = if denominator.0.abs() > denominator.1.abs() {
+            (h.0 - f.0 * v) / denominator.0
+        } else if denominator.1 != 0.0 {
+            (h.1 - f.1 * v) / denominator.1
+        } else {
+            continue;
+        };
+        if range.contains(&u) {
+            let (u, v) = (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+            return Some(vec![
+                (1.0 - u) * (1.0 - v),
+                u * (1.0 - v),
+                u * v,
+                (1.0 - u) * v,
+            ]);
+        }
+    }
+    None
+}
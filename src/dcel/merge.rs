@@ -0,0 +1,376 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt,
+};
+
+use super::{
+    geometry::{point_segment_distance, project_to_segment},
+    spatial::{QuadTree, Rect},
+    MeshCoverage,
+};
+
+// 两条边界边方向夹角的余弦小于该值（接近反向）时视为相对的接缝两侧
+const FACING_COS: f64 = -0.866;
+// 判断面内部重叠时允许的相对误差
+const OVERLAP_EPSILON: f64 = 1e-9;
+
+// 接缝处没有闭合的一对相对边界边，节点为合并后的 id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeamGap {
+    pub edge: (u32, u32),
+    pub other_edge: (u32, u32),
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    // other 的节点 id -> 合并后的节点 id
+    pub node_ids: HashMap<u32, u32>,
+    // other 的面 id -> 合并后的面 id
+    pub face_ids: HashMap<u32, u32>,
+    // 合并的重合边界节点：(保留的节点, other 的节点)
+    pub merged_nodes: Vec<(u32, u32)>,
+    // 接缝处的缝隙，edge 属于原网格，other_edge 属于 other
+    pub gaps: Vec<SeamGap>,
+    // 落在另一侧边界边上但没有对应节点的边界节点，接缝处不协调
+    pub hanging_nodes: Vec<u32>,
+    // 内部相互重叠的面：(原网格的面, other 合并后的面)
+    pub overlaps: Vec<(u32, u32)>,
+    // 合并后在部分节点或面上缺值的数据集
+    pub incomplete_datasets: Vec<String>,
+    // other 中引用了不存在的节点而没有加入的面
+    pub skipped_faces: Vec<u32>,
+}
+
+impl MergeReport {
+    pub fn is_conforming(&self) -> bool {
+        self.gaps.is_empty() && self.hanging_nodes.is_empty() && self.overlaps.is_empty()
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "merged nodes: {}", self.merged_nodes.len())?;
+        writeln!(f, "  gaps: {}", self.gaps.len())?;
+        writeln!(f, "  hanging nodes: {}", self.hanging_nodes.len())?;
+        write!(f, "  overlapping faces: {}", self.overlaps.len())?;
+        if !self.incomplete_datasets.is_empty() {
+            write!(f, "\n  incomplete datasets: {:?}", self.incomplete_datasets)?;
+        }
+        if !self.skipped_faces.is_empty() {
+            write!(f, "\n  skipped faces: {:?}", self.skipped_faces)?;
+        }
+        Ok(())
+    }
+}
+
+impl MeshCoverage {
+    // 将 other 合并到当前网格：距离不超过 tolerance 的边界节点一一合并（保留当前网格的坐标），
+    // 其余节点与面重新编号后加入，绕向不一致时翻转 other 的面；数据集一并加入；最后重建半边拓扑并检查接缝
+    pub fn merge(&mut self, other: &MeshCoverage, tolerance: f64) -> MergeReport {
+        let mut report = MergeReport::default();
+        let self_faces: HashSet<u32> = self.face_map.keys().copied().collect();
+
+        // 候选节点对按距离从小到大贪心匹配，保证一一对应
+        let self_boundary = self.get_boundary_node_set();
+        let mut other_boundary: Vec<u32> = other.get_boundary_node_set().into_iter().collect();
+        other_boundary.sort();
+        let mut candidates: Vec<(f64, u32, u32)> = Vec::new();
+        for other_id in other_boundary {
+            let node = other.node_map[&other_id];
+            let rect = Rect::new(
+                node.x - tolerance,
+                node.y - tolerance,
+                node.x + tolerance,
+                node.y + tolerance,
+            );
+            for self_id in self.node_index.query_rect(&rect) {
+                if !self_boundary.contains(&self_id) {
+                    continue;
+                }
+                let n = self.node_map[&self_id];
+                let distance = ((n.x - node.x).powi(2) + (n.y - node.y).powi(2)).sqrt();
+                if distance <= tolerance {
+                    candidates.push((distance, self_id, other_id));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+        let mut used: HashSet<u32> = HashSet::new();
+        for (_, self_id, other_id) in candidates {
+            if used.contains(&self_id) || report.node_ids.contains_key(&other_id) {
+                continue;
+            }
+            used.insert(self_id);
+            report.node_ids.insert(other_id, self_id);
+            report.merged_nodes.push((self_id, other_id));
+        }
+        report.merged_nodes.sort();
+
+        let mut node_ids: Vec<u32> = other.node_map.keys().copied().collect();
+        node_ids.sort();
+        for id in node_ids {
+            if let Entry::Vacant(entry) = report.node_ids.entry(id) {
+                let node = other.node_map[&id];
+                entry.insert(self.create_node(node.x, node.y, node.z));
+            }
+        }
+
+        let reverse =
+            !self_faces.is_empty() && self.get_face_winding() * other.get_face_winding() < 0.0;
+        let mut face_ids: Vec<u32> = other.face_map.keys().copied().collect();
+        face_ids.sort();
+        for id in face_ids {
+            let mut ids: Vec<u32> = match other.face_map[&id]
+                .node_ids()
+                .iter()
+                .map(|n| report.node_ids.get(n).copied())
+                .collect::<Option<Vec<u32>>>()
+            {
+                Some(ids) => ids,
+                None => {
+                    report.skipped_faces.push(id);
+                    continue;
+                }
+            };
+            if reverse {
+                ids[1..].reverse();
+            }
+            let new_id = self.create_face(
+                ids[0],
+                ids[1],
+                ids[2],
+                if ids.len() > 3 { ids[3] } else { 0 },
+            );
+            report.face_ids.insert(id, new_id);
+        }
+        self.generate_half_edges();
+        report.incomplete_datasets = self.merge_datasets(other, &report.node_ids, &report.face_ids);
+        self.decimated_points
+            .extend(other.decimated_points.iter().copied());
+
+        let other_faces: HashSet<u32> = report.face_ids.values().copied().collect();
+        self.check_seam(&other_faces, tolerance, &mut report);
+        report.overlaps = self.get_overlapping_faces(&self_faces, &other_faces);
+        report
+    }
+
+    // 边界半边的起点，不含面中引用了却不存在的节点
+    fn get_boundary_node_set(&self) -> HashSet<u32> {
+        self.half_edge_map
+            .values()
+            .filter(|half_edge| {
+                half_edge.face_id == 0 && self.node_map.contains_key(&half_edge.start_id)
+            })
+            .map(|half_edge| half_edge.start_id)
+            .collect()
+    }
+
+    // 检查合并后剩余的边界边：两侧相对且靠近的边为缝隙，落在对侧边界边中间的节点为悬挂节点
+    fn check_seam(&self, other_faces: &HashSet<u32>, tolerance: f64, report: &mut MergeReport) {
+        // 按边界半边内侧的面所属的网格分为两侧，各自建立空间索引
+        let mut sides: [QuadTree; 2] = [QuadTree::new(), QuadTree::new()];
+        let mut edges: [Vec<u32>; 2] = [Vec::new(), Vec::new()];
+        for (&id, half_edge) in &self.half_edge_map {
+            if half_edge.face_id != 0
+                || !self.node_map.contains_key(&half_edge.start_id)
+                || !self.node_map.contains_key(&half_edge.end_id)
+            {
+                continue;
+            }
+            let inner = self.half_edge_map[&half_edge.twin_id].face_id;
+            let side = other_faces.contains(&inner) as usize;
+            let points = self.get_face_points(&[half_edge.start_id, half_edge.end_id]);
+            if let Some(rect) = Rect::from_points(&points) {
+                sides[side].insert(id, rect);
+                edges[side].push(id);
+            }
+        }
+        edges[0].sort();
+        edges[1].sort();
+
+        for id in &edges[0] {
+            let a = self.get_half_edge_points(*id);
+            let len_a = distance(a[0], a[1]);
+            let rect = Rect::new(
+                a[0].0.min(a[1].0) - len_a,
+                a[0].1.min(a[1].1) - len_a,
+                a[0].0.max(a[1].0) + len_a,
+                a[0].1.max(a[1].1) + len_a,
+            );
+            let mut other_ids = sides[1].query_rect(&rect);
+            other_ids.sort();
+            for other_id in other_ids {
+                if self.share_node(*id, other_id) {
+                    continue;
+                }
+                let b = self.get_half_edge_points(other_id);
+                let len_b = distance(b[0], b[1]);
+                let dot =
+                    (a[1].0 - a[0].0) * (b[1].0 - b[0].0) + (a[1].1 - a[0].1) * (b[1].1 - b[0].1);
+                if len_a == 0.0 || len_b == 0.0 || dot / (len_a * len_b) > FACING_COS {
+                    continue;
+                }
+                // 对侧边投影到本边上的重叠长度
+                let t0 = project_to_segment(b[0], a[0], a[1]);
+                let t1 = project_to_segment(b[1], a[0], a[1]);
+                if (t0 - t1).abs() * len_a <= tolerance {
+                    continue;
+                }
+                let gap = segment_distance(a, b);
+                if gap < len_a.min(len_b) {
+                    report.gaps.push(SeamGap {
+                        edge: self.get_half_edge_nodes(*id),
+                        other_edge: self.get_half_edge_nodes(other_id),
+                        distance: gap,
+                    });
+                }
+            }
+        }
+
+        let mut hanging: HashSet<u32> = HashSet::new();
+        for side in 0..2 {
+            for id in &edges[side] {
+                let node_id = self.half_edge_map[id].start_id;
+                let node = self.node_map[&node_id];
+                let p = (node.x, node.y);
+                let rect = Rect::new(
+                    p.0 - tolerance,
+                    p.1 - tolerance,
+                    p.0 + tolerance,
+                    p.1 + tolerance,
+                );
+                for edge_id in sides[1 - side].query_rect(&rect) {
+                    let edge = self.half_edge_map[&edge_id];
+                    if edge.start_id == node_id || edge.end_id == node_id {
+                        continue;
+                    }
+                    let e = self.get_half_edge_points(edge_id);
+                    let len = distance(e[0], e[1]);
+                    let t = project_to_segment(p, e[0], e[1]);
+                    if point_segment_distance(p, e[0], e[1]) <= tolerance
+                        && t * len > tolerance
+                        && (1.0 - t) * len > tolerance
+                    {
+                        hanging.insert(node_id);
+                    }
+                }
+            }
+        }
+        report.hanging_nodes = hanging.into_iter().collect();
+        report.hanging_nodes.sort();
+    }
+
+    // 两组面中内部有重叠的面对，四边形按 (0, 1, 2)、(0, 2, 3) 拆成三角形判断
+    fn get_overlapping_faces(
+        &self,
+        faces: &HashSet<u32>,
+        other_faces: &HashSet<u32>,
+    ) -> Vec<(u32, u32)> {
+        let mut result = Vec::new();
+        for &other_id in other_faces {
+            let rect = match self.get_face_rect(other_id) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let other_triangles = self.get_face_triangles(other_id);
+            for face_id in self.face_index.query_rect(&rect) {
+                if !faces.contains(&face_id)
+                    || !self.face_map[&face_id]
+                        .node_ids()
+                        .iter()
+                        .all(|id| self.node_map.contains_key(id))
+                {
+                    continue;
+                }
+                let triangles = self.get_face_triangles(face_id);
+                if triangles
+                    .iter()
+                    .any(|t| other_triangles.iter().any(|o| triangles_overlap(t, o)))
+                {
+                    result.push((face_id, other_id));
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    fn get_face_triangles(&self, face_id: u32) -> Vec<[(f64, f64); 3]> {
+        let points = self.get_face_points(&self.face_map[&face_id].node_ids());
+        (1..points.len() - 1)
+            .map(|i| [points[0], points[i], points[i + 1]])
+            .collect()
+    }
+
+    fn get_half_edge_nodes(&self, id: u32) -> (u32, u32) {
+        let half_edge = self.half_edge_map[&id];
+        (half_edge.start_id, half_edge.end_id)
+    }
+
+    fn get_half_edge_points(&self, id: u32) -> [(f64, f64); 2] {
+        let (a, b) = self.get_half_edge_nodes(id);
+        let (a, b) = (self.node_map[&a], self.node_map[&b]);
+        [(a.x, a.y), (b.x, b.y)]
+    }
+
+    fn share_node(&self, a: u32, b: u32) -> bool {
+        let (a0, a1) = self.get_half_edge_nodes(a);
+        let (b0, b1) = self.get_half_edge_nodes(b);
+        a0 == b0 || a0 == b1 || a1 == b0 || a1 == b1
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn segment_distance(a: [(f64, f64); 2], b: [(f64, f64); 2]) -> f64 {
+    let d1 = cross(a[0], a[1], b[0]);
+    let d2 = cross(a[0], a[1], b[1]);
+    let d3 = cross(b[0], b[1], a[0]);
+    let d4 = cross(b[0], b[1], a[1]);
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return 0.0;
+    }
+    point_segment_distance(a[0], b[0], b[1])
+        .min(point_segment_distance(a[1], b[0], b[1]))
+        .min(point_segment_distance(b[0], a[0], a[1]))
+        .min(point_segment_distance(b[1], a[0], a[1]))
+}
+
+// 分离轴判断两个三角形内部是否重叠，只共享边或顶点时不算重叠
+fn triangles_overlap(a: &[(f64, f64); 3], b: &[(f64, f64); 3]) -> bool {
+    let points: Vec<(f64, f64)> = a.iter().chain(b.iter()).copied().collect();
+    let scale = match Rect::from_points(&points) {
+        Some(rect) => (rect.max_x - rect.min_x).max(rect.max_y - rect.min_y),
+        None => return false,
+    };
+    for triangle in [a, b] {
+        for i in 0..3 {
+            let (p, q) = (triangle[i], triangle[(i + 1) % 3]);
+            let normal = (q.1 - p.1, p.0 - q.0);
+            let length = (normal.0 * normal.0 + normal.1 * normal.1).sqrt();
+            if length == 0.0 {
+                return false;
+            }
+            let project = |t: &[(f64, f64); 3]| {
+                t.iter()
+                    .map(|v| (v.0 * normal.0 + v.1 * normal.1) / length)
+                    .fold((f64::MAX, f64::MIN), |(min, max), x| {
+                        (min.min(x), max.max(x))
+                    })
+            };
+            let (min_a, max_a) = project(a);
+            let (min_b, max_b) = project(b);
+            if max_a.min(max_b) - min_a.max(min_b) <= scale * OVERLAP_EPSILON {
+                return false;
+            }
+        }
+    }
+    true
+}
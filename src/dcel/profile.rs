@@ -0,0 +1,240 @@
+use std::{
+    collections::HashSet,
+    io::{Result, Write},
+};
+
+use super::{dataset::DatasetLocation, MeshCoverage};
+
+// 同一位置的判断容差，相对于线段长度
+const STATION_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilePoint {
+    // 沿折线从起点量起的距离，包括折线在网格外的部分
+    pub station: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    // 该点到下一点之间所在的面，最后一点为前一段所在的面；在网格外时为 0
+    pub face_id: u32,
+    // 与 Profile::dataset_names 一一对应，节点数据集按线性插值，面数据集取 face_id 上的值
+    pub values: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profile {
+    pub dataset_names: Vec<String>,
+    pub step: usize,
+    pub points: Vec<ProfilePoint>,
+}
+
+impl Profile {
+    // 逗号分隔，首行为列名，缺少的值留空
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut header = vec![
+            "station".to_string(),
+            "x".to_string(),
+            "y".to_string(),
+            "z".to_string(),
+            "face".to_string(),
+        ];
+        header.extend(self.dataset_names.iter().map(|name| csv_field(name)));
+        writeln!(writer, "{}", header.join(","))?;
+        for point in &self.points {
+            let mut fields = vec![
+                point.station.to_string(),
+                point.x.to_string(),
+                point.y.to_string(),
+                point.z.to_string(),
+                point.face_id.to_string(),
+            ];
+            fields.extend(
+                point
+                    .values
+                    .iter()
+                    .map(|value| value.map(|v| v.to_string()).unwrap_or_default()),
+            );
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// 剖面上的一个点及其在网格上的插值权重
+struct Crossing {
+    t: f64,
+    x: f64,
+    y: f64,
+    weights: Vec<(u32, f64)>,
+}
+
+impl MeshCoverage {
+    // 折线与网格边的交点，连同落在网格内的折线顶点，按沿线距离排序；
+    // 数据集取第 step 个时间步，数据集名称按字母排序
+    pub fn get_profile(&self, polyline: &[(f64, f64)], step: usize) -> Profile {
+        let dataset_names = self.get_dataset_names();
+        let mut points: Vec<ProfilePoint> = Vec::new();
+        let mut station = 0.0;
+        for (i, pair) in polyline.windows(2).enumerate() {
+            let (p, q) = (pair[0], pair[1]);
+            let length = (q.0 - p.0).hypot(q.1 - p.1);
+            let last = i + 2 == polyline.len();
+            for crossing in self.get_segment_crossings(p, q, last) {
+                let point_station = station + crossing.t * length;
+                if points.last().is_some_and(|point| {
+                    point_station - point.station <= STATION_EPSILON * length.max(1.0)
+                }) {
+                    continue;
+                }
+                let z = crossing
+                    .weights
+                    .iter()
+                    .map(|(id, w)| self.node_map[id].z * w)
+                    .sum();
+                let values = dataset_names
+                    .iter()
+                    .map(|name| {
+                        let dataset = &self.dataset_map[name];
+                        match dataset.location {
+                            DatasetLocation::Node => {
+                                let mut value = 0.0;
+                                for &(id, w) in &crossing.weights {
+                                    value += dataset.get_value(step, id)? * w;
+                                }
+                                Some(value)
+                            }
+                            DatasetLocation::Face => None,
+                        }
+                    })
+                    .collect();
+                points.push(ProfilePoint {
+                    station: point_station,
+                    x: crossing.x,
+                    y: crossing.y,
+                    z,
+                    face_id: 0,
+                    values,
+                });
+            }
+            station += length;
+        }
+
+        // 每一段所在的面由段中点确定，面数据集取该面上的值
+        let len = points.len();
+        for i in 0..len {
+            let (a, b) = if i + 1 < len {
+                (i, i + 1)
+            } else if i > 0 {
+                (i - 1, i)
+            } else {
+                (i, i)
+            };
+            let (x, y) = (
+                (points[a].x + points[b].x) / 2.0,
+                (points[a].y + points[b].y) / 2.0,
+            );
+            let face_id = self
+                .locate_point(x, y)
+                .map_or(0, |location| location.face_id);
+            points[i].face_id = face_id;
+            for (k, name) in dataset_names.iter().enumerate() {
+                let dataset = &self.dataset_map[name];
+                if dataset.location == DatasetLocation::Face {
+                    points[i].values[k] = dataset.get_value(step, face_id);
+                }
+            }
+        }
+        Profile {
+            dataset_names,
+            step,
+            points,
+        }
+    }
+
+    // 线段 pq 与网格边的交点，以及落在网格内的起点（include_end 时还有终点），按参数 t 排序
+    fn get_segment_crossings(
+        &self,
+        p: (f64, f64),
+        q: (f64, f64),
+        include_end: bool,
+    ) -> Vec<Crossing> {
+        let mut crossings: Vec<Crossing> = Vec::new();
+        let mut ends = vec![(0.0, p)];
+        if include_end {
+            ends.push((1.0, q));
+        }
+        for (t, (x, y)) in ends {
+            if let Some(location) = self.locate_point(x, y) {
+                crossings.push(Crossing {
+                    t,
+                    x,
+                    y,
+                    weights: location.weights,
+                });
+            }
+        }
+
+        let mut edges: HashSet<(u32, u32)> = HashSet::new();
+        for face_id in self.find_faces_along_segment(p, q) {
+            let ids = self.face_map[&face_id].node_ids();
+            let len = ids.len();
+            for i in 0..len {
+                let (a, b) = (ids[i], ids[(i + 1) % len]);
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        let mut edges: Vec<(u32, u32)> = edges.into_iter().collect();
+        edges.sort();
+
+        let r = (q.0 - p.0, q.1 - p.1);
+        let len2 = r.0 * r.0 + r.1 * r.1;
+        if len2 == 0.0 {
+            return crossings;
+        }
+        for (a, b) in edges {
+            let (na, nb) = (self.node_map[&a], self.node_map[&b]);
+            let s = (nb.x - na.x, nb.y - na.y);
+            let (qx, qy) = (na.x - p.0, na.y - p.1);
+            let denominator = r.0 * s.1 - r.1 * s.0;
+            if denominator == 0.0 {
+                // 沿边走时取落在线段上的边端点
+                if qx * r.1 - qy * r.0 != 0.0 {
+                    continue;
+                }
+                for (id, node) in [(a, na), (b, nb)] {
+                    let t = ((node.x - p.0) * r.0 + (node.y - p.1) * r.1) / len2;
+                    if (0.0..=1.0).contains(&t) {
+                        crossings.push(Crossing {
+                            t,
+                            x: node.x,
+                            y: node.y,
+                            weights: vec![(id, 1.0)],
+                        });
+                    }
+                }
+                continue;
+            }
+            let t = (qx * s.1 - qy * s.0) / denominator;
+            let u = (qx * r.1 - qy * r.0) / denominator;
+            if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&u) {
+                continue;
+            }
+            crossings.push(Crossing {
+                t,
+                x: na.x + s.0 * u,
+                y: na.y + s.1 * u,
+                weights: vec![(a, 1.0 - u), (b, u)],
+            });
+        }
+        crossings.sort_by(|c0, c1| c0.t.total_cmp(&c1.t));
+        crossings
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use super::{
+    geometry::{interior_angles, signed_area},
+    MeshCoverage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QualityMetric {
+    MinAngle,
+    MaxAngle,
+    AspectRatio,
+    Area,
+    Skewness,
+    AreaRatio,
+}
+
+impl QualityMetric {
+    pub const ALL: [QualityMetric; 6] = [
+        QualityMetric::MinAngle,
+        QualityMetric::MaxAngle,
+        QualityMetric::AspectRatio,
+        QualityMetric::Area,
+        QualityMetric::Skewness,
+        QualityMetric::AreaRatio,
+    ];
+
+    // 数值越大质量越好的指标，着色时反转色带
+    pub fn higher_is_better(&self) -> bool {
+        matches!(self, QualityMetric::MinAngle)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FaceQuality {
+    // 最小、最大内角，单位为度
+    pub min_angle: f64,
+    pub max_angle: f64,
+    // 最长边与最短边之比
+    pub aspect_ratio: f64,
+    pub area: f64,
+    // 等角偏斜度，0 为正多边形，1 为完全退化
+    pub skewness: f64,
+    // 与相邻面面积之比的最大值（大面积比小面积），没有相邻面时为 1
+    pub area_ratio: f64,
+}
+
+impl FaceQuality {
+    pub fn get(&self, metric: QualityMetric) -> f64 {
+        match metric {
+            QualityMetric::MinAngle => self.min_angle,
+            QualityMetric::MaxAngle => self.max_angle,
+            QualityMetric::AspectRatio => self.aspect_ratio,
+            QualityMetric::Area => self.area,
+            QualityMetric::Skewness => self.skewness,
+            QualityMetric::AreaRatio => self.area_ratio,
+        }
+    }
+}
+
+impl MeshCoverage {
+    // 所有面的质量指标，跳过引用了不存在节点的面
+    pub fn get_quality_map(&self) -> HashMap<u32, FaceQuality> {
+        let mut result: HashMap<u32, FaceQuality> = HashMap::new();
+        for (&face_id, face) in &self.face_map {
+            let ids = face.node_ids();
+            if ids.iter().all(|id| self.node_map.contains_key(id)) {
+                result.insert(face_id, self.get_face_shape_quality(&ids));
+            }
+        }
+
+        let areas: HashMap<u32, f64> = result.iter().map(|(&id, q)| (id, q.area)).collect();
+        for (face_id, quality) in result.iter_mut() {
+            for neighbour_id in self.get_face_neighbours(*face_id) {
+                if let Some(&area) = areas.get(&neighbour_id) {
+                    quality.area_ratio = quality.area_ratio.max(area_ratio(quality.area, area));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn get_face_quality(&self, face_id: u32) -> Option<FaceQuality> {
+        let ids = self.face_map.get(&face_id)?.node_ids();
+        if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+            return None;
+        }
+        let mut quality = self.get_face_shape_quality(&ids);
+        for neighbour_id in self.get_face_neighbours(face_id) {
+            if let Some(neighbour) = self.face_map.get(&neighbour_id) {
+                let neighbour_ids = neighbour.node_ids();
+                if neighbour_ids
+                    .iter()
+                    .all(|id| self.node_map.contains_key(id))
+                {
+                    let area = signed_area(&self.get_face_points(&neighbour_ids)).abs();
+                    quality.area_ratio = quality.area_ratio.max(area_ratio(quality.area, area));
+                }
+            }
+        }
+        Some(quality)
+    }
+
+    // 通过半边的对边找到共边的相邻面，需先调用 generate_half_edges
+    pub fn get_face_neighbours(&self, face_id: u32) -> Vec<u32> {
+        let mut result: Vec<u32> = self
+            .iter_face_neighbours(face_id)
+            .filter(|&id| id > 0 && id != face_id)
+            .collect();
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    fn get_face_shape_quality(&self, node_ids: &[u32]) -> FaceQuality {
+        let points = self.get_face_points(node_ids);
+        let angles = interior_angles(&points);
+        let min_angle = angles.iter().fold(f64::MAX, |a, &b| a.min(b));
+        let max_angle = angles.iter().fold(0.0, |a: f64, &b| a.max(b));
+
+        let len = points.len();
+        let mut min_edge = f64::MAX;
+        let mut max_edge: f64 = 0.0;
+        for i in 0..len {
+            let (p, q) = (points[i], points[(i + 1) % len]);
+            let edge = ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2)).sqrt();
+            min_edge = min_edge.min(edge);
+            max_edge = max_edge.max(edge);
+        }
+        let aspect_ratio = if min_edge > 0.0 {
+            max_edge / min_edge
+        } else {
+            f64::INFINITY
+        };
+
+        // 正多边形的内角
+        let equal_angle = 180.0 - 360.0 / len as f64;
+        let skewness = ((max_angle - equal_angle) / (180.0 - equal_angle))
+            .max((equal_angle - min_angle) / equal_angle)
+            .clamp(0.0, 1.0);
+
+        FaceQuality {
+            min_angle,
+            max_angle,
+            aspect_ratio,
+            area: signed_area(&points).abs(),
+            skewness,
+            area_ratio: 1.0,
+        }
+    }
+}
+
+fn area_ratio(a: f64, b: f64) -> f64 {
+    let (small, large) = (a.min(b), a.max(b));
+    if small > 0.0 {
+        large / small
+    } else {
+        f64::INFINITY
+    }
+}
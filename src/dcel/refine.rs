@@ -0,0 +1,217 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{geometry::point_in_polygon, MeshCoverage};
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    (a.min(b), a.max(b))
+}
+
+impl MeshCoverage {
+    // 整体加密，每个三角形和四边形都拆分为四个，返回新建的面
+    pub fn refine_all(&mut self) -> Vec<u32> {
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+        self.refine_faces(&face_ids)
+    }
+
+    // 加密形心在多边形内的面，多边形首尾点不重复
+    pub fn refine_in_polygon(&mut self, polygon: &[(f64, f64)]) -> Vec<u32> {
+        let mut face_ids: Vec<u32> = self
+            .face_map
+            .iter()
+            .filter(|(&id, face)| {
+                if !self.has_face_nodes(id) {
+                    return false;
+                }
+                let points = self.get_face_points(&face.node_ids());
+                let len = points.len() as f64;
+                let x = points.iter().map(|p| p.0).sum::<f64>() / len;
+                let y = points.iter().map(|p| p.1).sum::<f64>() / len;
+                point_in_polygon((x, y), polygon)
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        face_ids.sort();
+        self.refine_faces(&face_ids)
+    }
+
+    // 将选中的面一分为四，并在加密区域边缘生成过渡单元，保证没有悬挂节点；返回新建的面。
+    // 引用了不存在的节点的面不加密
+    pub fn refine_faces(&mut self, face_ids: &[u32]) -> Vec<u32> {
+        let mut refined: HashSet<u32> = HashSet::new();
+        let mut split_edges: HashSet<(u32, u32)> = HashSet::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        for face_id in face_ids {
+            if self.has_face_nodes(*face_id) && refined.insert(*face_id) {
+                queue.push_back(*face_id);
+            }
+        }
+
+        // 有两条以上加密边的三角形、三条以上加密边的四边形也整体加密
+        while let Some(face_id) = queue.pop_front() {
+            let ids = self.face_map[&face_id].node_ids();
+            for i in 0..ids.len() {
+                split_edges.insert(edge_key(ids[i], ids[(i + 1) % ids.len()]));
+            }
+            let mut neighbour_ids: Vec<u32> = Vec::new();
+            for id in &ids {
+                if let Some(set) = self.node_face_adj.get_node_adj_faces(*id) {
+                    neighbour_ids.extend(set.iter().copied());
+                }
+            }
+            neighbour_ids.sort();
+            neighbour_ids.dedup();
+            for neighbour_id in neighbour_ids {
+                if refined.contains(&neighbour_id) {
+                    continue;
+                }
+                let neighbour = self.face_map[&neighbour_id].node_ids();
+                let count = (0..neighbour.len())
+                    .filter(|&i| {
+                        split_edges.contains(&edge_key(
+                            neighbour[i],
+                            neighbour[(i + 1) % neighbour.len()],
+                        ))
+                    })
+                    .count();
+                if count >= neighbour.len() - 1 && self.has_face_nodes(neighbour_id) {
+                    refined.insert(neighbour_id);
+                    queue.push_back(neighbour_id);
+                }
+            }
+        }
+
+        // 边中点
+        let mut edges: Vec<(u32, u32)> = split_edges.into_iter().collect();
+        edges.sort();
+        let mut middle: HashMap<(u32, u32), u32> = HashMap::new();
+        for (a, b) in edges {
+            let (p, q) = (self.node_map[&a], self.node_map[&b]);
+            let id = self.create_node((p.x + q.x) / 2.0, (p.y + q.y) / 2.0, (p.z + q.z) / 2.0);
+            self.interpolate_node_values(id, &[(a, 0.5), (b, 0.5)]);
+            middle.insert((a, b), id);
+        }
+
+        // 受影响的面：整体加密的面及含有加密边的过渡面
+        let mut affected: Vec<u32> = Vec::new();
+        for (&face_id, face) in &self.face_map {
+            let ids = face.node_ids();
+            let has_split = (0..ids.len())
+                .any(|i| middle.contains_key(&edge_key(ids[i], ids[(i + 1) % ids.len()])));
+            if has_split {
+                affected.push(face_id);
+            }
+        }
+        affected.sort();
+
+        // 新面取所在原面的数据集值
+        let mut new_faces: Vec<(u32, Vec<u32>)> = Vec::new();
+        for &face_id in &affected {
+            let ids = self.face_map[&face_id].node_ids();
+            let len = ids.len();
+            let mids: Vec<Option<u32>> = (0..len)
+                .map(|i| middle.get(&edge_key(ids[i], ids[(i + 1) % len])).copied())
+                .collect();
+            let faces = if refined.contains(&face_id) {
+                self.get_refined_faces(&ids, &mids)
+            } else {
+                get_transition_faces(&ids, &mids)
+            };
+            new_faces.extend(faces.into_iter().map(|ids| (face_id, ids)));
+        }
+
+        let values = self.get_face_values(&affected);
+        for face_id in affected {
+            self.remove_face(face_id);
+        }
+        new_faces
+            .into_iter()
+            .map(|(source_id, ids)| {
+                let face_id = self.add_face(
+                    ids[0],
+                    ids[1],
+                    ids[2],
+                    if ids.len() > 3 { ids[3] } else { 0 },
+                );
+                self.interpolate_face_values(&values, face_id, &[(source_id, 1.0)]);
+                face_id
+            })
+            .collect()
+    }
+
+    fn has_face_nodes(&self, face_id: u32) -> bool {
+        self.face_map.get(&face_id).is_some_and(|face| {
+            face.node_ids()
+                .iter()
+                .all(|id| self.node_map.contains_key(id))
+        })
+    }
+
+    // 三角形连接三边中点，四边形连接四边中点与形心
+    fn get_refined_faces(&mut self, ids: &[u32], mids: &[Option<u32>]) -> Vec<Vec<u32>> {
+        let m: Vec<u32> = mids.iter().map(|m| m.unwrap()).collect();
+        if ids.len() == 3 {
+            let [a, b, c] = [ids[0], ids[1], ids[2]];
+            return vec![
+                vec![a, m[0], m[2]],
+                vec![m[0], b, m[1]],
+                vec![m[2], m[1], c],
+                vec![m[0], m[1], m[2]],
+            ];
+        }
+        let nodes: Vec<_> = ids.iter().map(|id| self.node_map[id]).collect();
+        let center = self.create_node(
+            nodes.iter().map(|n| n.x).sum::<f64>() / 4.0,
+            nodes.iter().map(|n| n.y).sum::<f64>() / 4.0,
+            nodes.iter().map(|n| n.z).sum::<f64>() / 4.0,
+        );
+        let weights: Vec<(u32, f64)> = ids.iter().map(|id| (*id, 0.25)).collect();
+        self.interpolate_node_values(center, &weights);
+        let [a, b, c, d] = [ids[0], ids[1], ids[2], ids[3]];
+        vec![
+            vec![a, m[0], center, m[3]],
+            vec![m[0], b, m[1], center],
+            vec![center, m[1], c, m[2]],
+            vec![m[3], center, m[2], d],
+        ]
+    }
+}
+
+// 过渡单元：三角形一条加密边，四边形一条或两条加密边
+fn get_transition_faces(ids: &[u32], mids: &[Option<u32>]) -> Vec<Vec<u32>> {
+    let len = ids.len();
+    let split: Vec<usize> = (0..len).filter(|&i| mids[i].is_some()).collect();
+    // 旋转使第一条加密边为 ids[0]-ids[1]
+    let start = split[0];
+    let r: Vec<u32> = (0..len).map(|i| ids[(start + i) % len]).collect();
+    let m0 = mids[start].unwrap();
+
+    if len == 3 {
+        return vec![vec![r[0], m0, r[2]], vec![m0, r[1], r[2]]];
+    }
+    if split.len() == 1 {
+        return vec![
+            vec![r[0], m0, r[3]],
+            vec![m0, r[1], r[2]],
+            vec![m0, r[2], r[3]],
+        ];
+    }
+    let other = (split[1] + len - start) % len;
+    let m1 = mids[split[1]].unwrap();
+    match other {
+        // 对边
+        2 => vec![vec![r[0], m0, m1, r[3]], vec![m0, r[1], r[2], m1]],
+        // 相邻边 r1-r2
+        1 => vec![
+            vec![m0, r[1], m1],
+            vec![m1, r[2], r[3]],
+            vec![r[0], m0, m1, r[3]],
+        ],
+        // 相邻边 r3-r0，旋转后第一条加密边为 r3-r0
+        _ => vec![
+            vec![m1, r[0], m0],
+            vec![m0, r[1], r[2]],
+            vec![r[3], m1, m0, r[2]],
+        ],
+    }
+}
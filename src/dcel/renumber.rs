@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{dataset::DatasetLocation, MeshCoverage};
+
+#[derive(Debug, Clone, Default)]
+pub struct Renumbering {
+    // 旧 id -> 新 id
+    pub node_ids: HashMap<u32, u32>,
+    pub face_ids: HashMap<u32, u32>,
+    // 引用了不存在节点而被删除的面（旧 id），见 MeshCoverage::validate
+    pub dropped_faces: Vec<u32>,
+    pub bandwidth_before: u32,
+    pub bandwidth_after: u32,
+}
+
+impl MeshCoverage {
+    // 按导出顺序（节点 id 从小到大，从 1 开始连续编号）计算的带宽：同一面上两节点序号之差的最大值
+    pub fn get_bandwidth(&self) -> u32 {
+        let mut node_ids: Vec<u32> = self.node_map.keys().copied().collect();
+        node_ids.sort();
+        let index: HashMap<u32, u32> = node_ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, i as u32))
+            .collect();
+        let mut result = 0;
+        for face in self.face_map.values() {
+            let positions: Vec<u32> = face
+                .node_ids()
+                .iter()
+                .filter_map(|id| index.get(id).copied())
+                .collect();
+            if let (Some(min), Some(max)) = (positions.iter().min(), positions.iter().max()) {
+                result = result.max(max - min);
+            }
+        }
+        result
+    }
+
+    // 按逆 Cuthill-McKee 顺序将节点重新编号为 1..=n，renumber_faces 为 true 时面按最小节点编号排序后重新编号为 1..=m，
+    // 否则保留面的 id；引用了不存在节点的面被删除；半边拓扑重新生成
+    pub fn renumber(&mut self, renumber_faces: bool) -> Renumbering {
+        let bandwidth_before = self.get_bandwidth();
+
+        let node_ids: HashMap<u32, u32> = self
+            .get_rcm_order()
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, i as u32 + 1))
+            .collect();
+
+        let mut faces: Vec<(u32, [u32; 4])> = Vec::new();
+        let mut dropped_faces: Vec<u32> = Vec::new();
+        for (&id, face) in &self.face_map {
+            let map = |id: u32| {
+                if id > 0 {
+                    node_ids.get(&id).copied()
+                } else {
+                    Some(0)
+                }
+            };
+            match (map(face.n0), map(face.n1), map(face.n2), map(face.n3)) {
+                (Some(n0), Some(n1), Some(n2), Some(n3)) => faces.push((id, [n0, n1, n2, n3])),
+                _ => dropped_faces.push(id),
+            }
+        }
+        dropped_faces.sort();
+        if renumber_faces {
+            // 面按经过的最小、最大节点编号排序，与节点顺序一致
+            faces.sort_by_key(|(id, n)| {
+                let nodes = n.iter().filter(|&&n| n > 0);
+                (nodes.clone().min().copied(), nodes.max().copied(), *id)
+            });
+        } else {
+            faces.sort_by_key(|(id, _)| *id);
+        }
+        let face_ids: HashMap<u32, u32> = faces
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, if renumber_faces { i as u32 + 1 } else { *id }))
+            .collect();
+
+        let mut coverage = MeshCoverage::new(self.id.clone());
+        let mut nodes: Vec<(u32, u32)> = node_ids.iter().map(|(&old, &new)| (new, old)).collect();
+        nodes.sort();
+        for (new, old) in nodes {
+            let node = self.node_map[&old];
+            coverage.create_node_with_id(new, node.x, node.y, node.z);
+        }
+        for (id, [n0, n1, n2, n3]) in faces {
+            coverage.create_face_with_id(face_ids[&id], n0, n1, n2, n3);
+        }
+        coverage.generate_half_edges();
+        for (name, mut dataset) in std::mem::take(&mut self.dataset_map) {
+            match dataset.location {
+                DatasetLocation::Node => dataset.renumber(&node_ids),
+                DatasetLocation::Face => dataset.renumber(&face_ids),
+            }
+            coverage.dataset_map.insert(name, dataset);
+        }
+        *self = coverage;
+
+        Renumbering {
+            node_ids,
+            face_ids,
+            dropped_faces,
+            bandwidth_before,
+            bandwidth_after: self.get_bandwidth(),
+        }
+    }
+
+    // 同一面上的节点两两相连构成的图，对应求解器矩阵的非零元
+    fn get_node_graph(&self) -> HashMap<u32, Vec<u32>> {
+        let mut graph: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for face in self.face_map.values() {
+            let ids: Vec<u32> = face
+                .node_ids()
+                .into_iter()
+                .filter(|id| self.node_map.contains_key(id))
+                .collect();
+            for &a in &ids {
+                for &b in &ids {
+                    if a != b {
+                        graph.entry(a).or_default().insert(b);
+                    }
+                }
+            }
+        }
+        graph
+            .into_iter()
+            .map(|(id, set)| {
+                let mut neighbours: Vec<u32> = set.into_iter().collect();
+                neighbours.sort();
+                (id, neighbours)
+            })
+            .collect()
+    }
+
+    // 逐个连通分量从伪外围节点出发广度优先遍历，相邻节点按度数从小到大加入，最后整体反转；
+    // 不属于任何面的节点放在最后
+    fn get_rcm_order(&self) -> Vec<u32> {
+        let graph = self.get_node_graph();
+        let degree = |id: u32| graph.get(&id).map_or(0, |n| n.len());
+
+        let mut node_ids: Vec<u32> = graph.keys().copied().collect();
+        node_ids.sort_by_key(|&id| (degree(id), id));
+
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut order: Vec<u32> = Vec::with_capacity(self.node_map.len());
+        for &seed in &node_ids {
+            if visited.contains(&seed) {
+                continue;
+            }
+            let start = get_pseudo_peripheral_node(&graph, seed);
+            let mut queue: VecDeque<u32> = VecDeque::new();
+            visited.insert(start);
+            queue.push_back(start);
+            while let Some(id) = queue.pop_front() {
+                order.push(id);
+                let mut neighbours: Vec<u32> = graph[&id]
+                    .iter()
+                    .copied()
+                    .filter(|n| !visited.contains(n))
+                    .collect();
+                neighbours.sort_by_key(|&n| (degree(n), n));
+                for n in neighbours {
+                    visited.insert(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+        order.reverse();
+
+        let mut isolated: Vec<u32> = self
+            .node_map
+            .keys()
+            .copied()
+            .filter(|id| !graph.contains_key(id))
+            .collect();
+        isolated.sort();
+        order.extend(isolated);
+        order
+    }
+}
+
+// 广度优先分层，返回层数及最后一层的节点
+fn get_level_structure(graph: &HashMap<u32, Vec<u32>>, start: u32) -> (usize, Vec<u32>) {
+    let mut visited: HashSet<u32> = HashSet::new();
+    visited.insert(start);
+    let mut level = vec![start];
+    let mut depth = 1;
+    loop {
+        let mut next: Vec<u32> = Vec::new();
+        for id in &level {
+            for &n in &graph[id] {
+                if visited.insert(n) {
+                    next.push(n);
+                }
+            }
+        }
+        if next.is_empty() {
+            return (depth, level);
+        }
+        level = next;
+        depth += 1;
+    }
+}
+
+// George-Liu 算法：反复取最后一层中度数最小的节点，直到层数不再增加
+fn get_pseudo_peripheral_node(graph: &HashMap<u32, Vec<u32>>, seed: u32) -> u32 {
+    let mut start = seed;
+    let (mut depth, mut last) = get_level_structure(graph, start);
+    loop {
+        let candidate = *last
+            .iter()
+            .min_by_key(|&&id| (graph[&id].len(), id))
+            .unwrap();
+        let (candidate_depth, candidate_last) = get_level_structure(graph, candidate);
+        if candidate_depth <= depth {
+            return start;
+        }
+        start = candidate;
+        depth = candidate_depth;
+        last = candidate_last;
+    }
+}
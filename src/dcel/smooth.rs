@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    geometry::{interior_angles, is_same_winding, signed_area},
+    MeshCoverage,
+};
+
+// 边界点前后两条边夹角的正弦小于该值时视为直线，可沿边界滑动
+const STRAIGHT_BOUNDARY_SIN: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmoothMethod {
+    // 移动到相邻节点的平均位置
+    Laplacian,
+    // 绕各相邻节点旋转，使其两侧夹角相等（Zhou & Shimada）
+    AngleBased,
+    // 在上述候选位置中选择使周围面最小角最大的位置，且只在变好时移动
+    Optimized,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmoothZ {
+    // 在原网格上插值新位置的高程
+    Interpolate,
+    // 保持原高程
+    Fixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmoothOptions {
+    pub method: SmoothMethod,
+    pub iterations: usize,
+    // 松弛系数，0~1，1 为直接移动到目标位置
+    pub relaxation: f64,
+    pub lock_boundary: bool,
+    pub locked_nodes: HashSet<u32>,
+    pub z: SmoothZ,
+}
+
+impl Default for SmoothOptions {
+    fn default() -> Self {
+        Self {
+            method: SmoothMethod::Laplacian,
+            iterations: 5,
+            relaxation: 1.0,
+            lock_boundary: true,
+            locked_nodes: HashSet::new(),
+            z: SmoothZ::Interpolate,
+        }
+    }
+}
+
+impl MeshCoverage {
+    // 光顺网格节点，逐点更新，会使相邻面翻转或退化的移动被放弃；返回移动过的节点数
+    pub fn smooth(&mut self, options: &SmoothOptions) -> usize {
+        let mut node_ids: Vec<u32> = self
+            .node_map
+            .keys()
+            .filter(|id| !options.locked_nodes.contains(id))
+            .copied()
+            .collect();
+        node_ids.sort();
+
+        let mut moved: HashSet<u32> = HashSet::new();
+        for _ in 0..options.iterations {
+            let mut changed = false;
+            for &node_id in &node_ids {
+                if self.smooth_node(node_id, options) {
+                    moved.insert(node_id);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        moved.len()
+    }
+
+    fn smooth_node(&mut self, node_id: u32, options: &SmoothOptions) -> bool {
+        let face_ids: Vec<u32> = match self.node_face_adj.get_node_adj_faces(node_id) {
+            Some(set) if !set.is_empty() => set.iter().copied().collect(),
+            _ => return false,
+        };
+        // 周围的面引用了不存在的节点时视为锁定
+        if !face_ids.iter().all(|face_id| {
+            self.face_map[face_id]
+                .node_ids()
+                .iter()
+                .all(|id| self.node_map.contains_key(id))
+        }) {
+            return false;
+        }
+        let node = self.node_map[&node_id];
+        let current = (node.x, node.y);
+
+        let target = if self.is_boundary_node(node_id) {
+            if options.lock_boundary {
+                return false;
+            }
+            match self.get_boundary_slide_target(node_id, &face_ids) {
+                Some(target) => target,
+                None => return false,
+            }
+        } else {
+            let candidates = match options.method {
+                SmoothMethod::Laplacian => vec![self.get_laplacian_target(node_id)],
+                SmoothMethod::AngleBased => vec![self.get_angle_based_target(node_id, &face_ids)],
+                SmoothMethod::Optimized => {
+                    let laplacian = self.get_laplacian_target(node_id);
+                    let angle = self.get_angle_based_target(node_id, &face_ids);
+                    let middle = ((laplacian.0 + angle.0) / 2.0, (laplacian.1 + angle.1) / 2.0);
+                    vec![laplacian, angle, middle]
+                }
+            };
+            let mut best: Option<((f64, f64), f64)> = None;
+            for candidate in candidates {
+                let target = (
+                    current.0 + (candidate.0 - current.0) * options.relaxation,
+                    current.1 + (candidate.1 - current.1) * options.relaxation,
+                );
+                if let Some(quality) = self.get_star_min_angle(node_id, &face_ids, target) {
+                    let better = match best {
+                        Some((_, best_quality)) => quality > best_quality,
+                        None => true,
+                    };
+                    if better {
+                        best = Some((target, quality));
+                    }
+                }
+            }
+            match best {
+                Some((target, quality)) => {
+                    if options.method == SmoothMethod::Optimized {
+                        let before = self.get_star_min_angle(node_id, &face_ids, current);
+                        if before.is_some_and(|before| quality <= before) {
+                            return false;
+                        }
+                    }
+                    target
+                }
+                None => return false,
+            }
+        };
+
+        if target == current
+            || self
+                .get_star_min_angle(node_id, &face_ids, target)
+                .is_none()
+        {
+            return false;
+        }
+        let z = match options.z {
+            SmoothZ::Interpolate => self.interpolate_star_z(&face_ids, target).unwrap_or(node.z),
+            SmoothZ::Fixed => node.z,
+        };
+        self.set_node_position(node_id, target.0, target.1, z);
+        true
+    }
+
+    fn get_laplacian_target(&self, node_id: u32) -> (f64, f64) {
+        let neighbours = self.get_node_neighbours(node_id);
+        let (mut x, mut y) = (0.0, 0.0);
+        for id in &neighbours {
+            let node = self.node_map[id];
+            x += node.x;
+            y += node.y;
+        }
+        let len = neighbours.len() as f64;
+        (x / len, y / len)
+    }
+
+    // 对每个相邻节点 n，将当前点绕 n 旋转到 n 处两侧面夹角的平分线上，再取平均
+    fn get_angle_based_target(&self, node_id: u32, face_ids: &[u32]) -> (f64, f64) {
+        // 相邻节点 -> 该节点在各个面中除当前点外的另一个相邻节点
+        let mut sides: HashMap<u32, Vec<u32>> = HashMap::new();
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            let len = ids.len();
+            if let Some(i) = ids.iter().position(|&id| id == node_id) {
+                sides
+                    .entry(ids[(i + 1) % len])
+                    .or_default()
+                    .push(ids[(i + 2) % len]);
+                sides
+                    .entry(ids[(i + len - 1) % len])
+                    .or_default()
+                    .push(ids[(i + len - 2) % len]);
+            }
+        }
+
+        let node = self.node_map[&node_id];
+        let (mut x, mut y, mut count) = (0.0, 0.0, 0.0);
+        for (neighbour_id, others) in sides {
+            let n = self.node_map[&neighbour_id];
+            let (px, py) = (node.x - n.x, node.y - n.y);
+            let target = if others.len() == 2 {
+                let u1 = self.node_map[&others[0]];
+                let u2 = self.node_map[&others[1]];
+                let theta = py.atan2(px);
+                let a1 = wrap_angle(theta - (u1.y - n.y).atan2(u1.x - n.x));
+                let a2 = wrap_angle((u2.y - n.y).atan2(u2.x - n.x) - theta);
+                let beta = (a2 - a1) / 2.0;
+                let (sin, cos) = beta.sin_cos();
+                (n.x + px * cos - py * sin, n.y + px * sin + py * cos)
+            } else {
+                (n.x, n.y)
+            };
+            x += target.0;
+            y += target.1;
+            count += 1.0;
+        }
+        (x / count, y / count)
+    }
+
+    // 直线段上的边界点沿边界移动到两侧边界点的中点
+    fn get_boundary_slide_target(&self, node_id: u32, face_ids: &[u32]) -> Option<(f64, f64)> {
+        let mut edge_count: HashMap<u32, usize> = HashMap::new();
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            let len = ids.len();
+            let i = ids.iter().position(|&id| id == node_id)?;
+            *edge_count.entry(ids[(i + 1) % len]).or_default() += 1;
+            *edge_count.entry(ids[(i + len - 1) % len]).or_default() += 1;
+        }
+        let ends: Vec<u32> = edge_count
+            .into_iter()
+            .filter(|(_, count)| *count == 1)
+            .map(|(id, _)| id)
+            .collect();
+        if ends.len() != 2 {
+            return None;
+        }
+        let node = self.node_map[&node_id];
+        let a = self.node_map[&ends[0]];
+        let b = self.node_map[&ends[1]];
+        let (ax, ay) = (a.x - node.x, a.y - node.y);
+        let (bx, by) = (b.x - node.x, b.y - node.y);
+        let len = (ax * ax + ay * ay).sqrt() * (bx * bx + by * by).sqrt();
+        if len == 0.0
+            || (ax * by - ay * bx).abs() / len > STRAIGHT_BOUNDARY_SIN
+            || ax * bx + ay * by > 0.0
+        {
+            return None;
+        }
+        Some(((a.x + b.x) / 2.0, (a.y + b.y) / 2.0))
+    }
+
+    // 节点移动到 position 后周围面的最小内角；有面翻转或退化时返回 None
+    fn get_star_min_angle(
+        &self,
+        node_id: u32,
+        face_ids: &[u32],
+        position: (f64, f64),
+    ) -> Option<f64> {
+        let mut result = f64::MAX;
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            let sign = signed_area(&self.get_face_points(&ids)).signum();
+            let points: Vec<(f64, f64)> = ids
+                .iter()
+                .map(|id| {
+                    if *id == node_id {
+                        position
+                    } else {
+                        let node = self.node_map[id];
+                        (node.x, node.y)
+                    }
+                })
+                .collect();
+            if !is_same_winding(&points, sign) {
+                return None;
+            }
+            // 不允许产生凹四边形
+            for angle in interior_angles(&points) {
+                if angle >= 180.0 {
+                    return None;
+                }
+                result = result.min(angle);
+            }
+        }
+        Some(result)
+    }
+
+    // 在移动前的周围面上线性插值 position 处的高程，四边形按 (0, 1, 2)、(0, 2, 3) 拆分
+    fn interpolate_star_z(&self, face_ids: &[u32], position: (f64, f64)) -> Option<f64> {
+        for face_id in face_ids {
+            let ids = self.face_map[face_id].node_ids();
+            for i in 1..ids.len() - 1 {
+                let [a, b, c] = [ids[0], ids[i], ids[i + 1]].map(|id| self.node_map[&id]);
+                let area = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+                if area == 0.0 {
+                    continue;
+                }
+                let wa = ((b.x - position.0) * (c.y - position.1)
+                    - (b.y - position.1) * (c.x - position.0))
+                    / area;
+                let wb = ((c.x - position.0) * (a.y - position.1)
+                    - (c.y - position.1) * (a.x - position.0))
+                    / area;
+                let wc = 1.0 - wa - wb;
+                if wa >= -1e-12 && wb >= -1e-12 && wc >= -1e-12 {
+                    return Some(wa * a.z + wb * b.z + wc * c.z);
+                }
+            }
+        }
+        None
+    }
+}
+
+// 角度归一化到 (-PI, PI]
+fn wrap_angle(angle: f64) -> f64 {
+    let mut angle = angle;
+    while angle <= -std::f64::consts::PI {
+        angle += 2.0 * std::f64::consts::PI;
+    }
+    while angle > std::f64::consts::PI {
+        angle -= 2.0 * std::f64::consts::PI;
+    }
+    angle
+}
@@ -0,0 +1,475 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::{Error, ErrorKind, Result},
+};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::{
+    dataset::{Dataset, DatasetLocation, DatasetValues},
+    Face, FaceHalfEdgeAdj, HalfEdge, IdGenerator, MeshCoverage, Node, NodeFaceAdj,
+};
+
+const MAGIC: &[u8; 8] = b"MESHSNAP";
+const VERSION: u32 = 1;
+
+// 按键排序写出 HashMap，使相同的网格得到相同的输出
+pub fn serialize_map<S, K, V>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+pub fn serialize_set_map<S: Serializer>(
+    map: &HashMap<u32, HashSet<u32>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        map.iter()
+            .map(|(k, set)| (k, set.iter().collect::<BTreeSet<_>>()))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+// MeshCoverage 除空间索引外的字段，反序列化后转换为 MeshCoverage，检查引用并重新生成空间索引
+#[derive(Deserialize)]
+pub struct MeshCoverageData {
+    id: String,
+    node_map: HashMap<u32, Node>,
+    face_map: HashMap<u32, Face>,
+    half_edge_map: HashMap<u32, HalfEdge>,
+    node_face_adj: NodeFaceAdj,
+    face_half_edge_adj: FaceHalfEdgeAdj,
+    node_id_generator: IdGenerator,
+    face_id_generator: IdGenerator,
+    half_edge_id_generator: IdGenerator,
+    #[serde(default)]
+    dataset_map: HashMap<String, Dataset>,
+}
+
+impl TryFrom<MeshCoverageData> for MeshCoverage {
+    type Error = Error;
+
+    fn try_from(data: MeshCoverageData) -> Result<Self> {
+        let mut coverage = MeshCoverage::new(data.id);
+        coverage.node_map = data.node_map;
+        coverage.face_map = data.face_map;
+        coverage.half_edge_map = data.half_edge_map;
+        coverage.node_face_adj = data.node_face_adj;
+        coverage.face_half_edge_adj = data.face_half_edge_adj;
+        coverage.node_id_generator = data.node_id_generator;
+        coverage.face_id_generator = data.face_id_generator;
+        coverage.half_edge_id_generator = data.half_edge_id_generator;
+        coverage.dataset_map = data.dataset_map;
+        coverage.check_references()?;
+        coverage.rebuild_index();
+        Ok(coverage)
+    }
+}
+
+impl MeshCoverage {
+    // 紧凑的二进制快照，数值均为小端：
+    // MESHSNAP、版本、id、三个 id 分配器的当前值，随后依次为节点、面、半边及数据集，各部分以个数开头并按 id 排序；
+    // 节点与面、面与半边的邻接关系由面和半边直接得到，不写出，读取时也不需要重新生成半边
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut writer = SnapshotWriter {
+            bytes: Vec::with_capacity(
+                32 + self.node_map.len() * 28
+                    + self.face_map.len() * 20
+                    + self.half_edge_map.len() * 28,
+            ),
+        };
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.u32(VERSION);
+        writer.string(&self.id);
+        writer.u32(self.node_id_generator.current());
+        writer.u32(self.face_id_generator.current());
+        writer.u32(self.half_edge_id_generator.current());
+
+        writer.u32(self.node_map.len() as u32);
+        for (id, node) in self.node_map.iter().collect::<BTreeMap<_, _>>() {
+            writer.u32(*id);
+            writer.f64(node.x);
+            writer.f64(node.y);
+            writer.f64(node.z);
+        }
+        writer.u32(self.face_map.len() as u32);
+        for (id, face) in self.face_map.iter().collect::<BTreeMap<_, _>>() {
+            for value in [*id, face.n0, face.n1, face.n2, face.n3] {
+                writer.u32(value);
+            }
+        }
+        writer.u32(self.half_edge_map.len() as u32);
+        for (id, h) in self.half_edge_map.iter().collect::<BTreeMap<_, _>>() {
+            for value in [
+                *id, h.start_id, h.end_id, h.face_id, h.prev_id, h.next_id, h.twin_id,
+            ] {
+                writer.u32(value);
+            }
+        }
+
+        writer.u32(self.dataset_map.len() as u32);
+        for dataset in self.dataset_map.iter().collect::<BTreeMap<_, _>>().values() {
+            writer.string(&dataset.name);
+            writer.bytes.push(match dataset.location {
+                DatasetLocation::Node => 0,
+                DatasetLocation::Face => 1,
+            });
+            writer.u32(dataset.steps.len() as u32);
+            for step in &dataset.steps {
+                writer.f64(step.time);
+                match &step.values {
+                    DatasetValues::Scalar(map) => {
+                        writer.bytes.push(0);
+                        writer.u32(map.len() as u32);
+                        for (id, value) in map.iter().collect::<BTreeMap<_, _>>() {
+                            writer.u32(*id);
+                            writer.f64(*value);
+                        }
+                    }
+                    DatasetValues::Vector(map) => {
+                        writer.bytes.push(1);
+                        writer.u32(map.len() as u32);
+                        for (id, (x, y)) in map.iter().collect::<BTreeMap<_, _>>() {
+                            writer.u32(*id);
+                            writer.f64(*x);
+                            writer.f64(*y);
+                        }
+                    }
+                }
+            }
+        }
+        writer.bytes
+    }
+
+    pub fn from_snapshot(bytes: &[u8]) -> Result<MeshCoverage> {
+        let mut reader = SnapshotReader { bytes, offset: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid_data("not a mesh snapshot"));
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported snapshot version {}",
+                version
+            )));
+        }
+        let mut coverage = MeshCoverage::new(reader.string()?);
+        let node_current = reader.u32()?;
+        let face_current = reader.u32()?;
+        let half_edge_current = reader.u32()?;
+
+        for _ in 0..reader.u32()? {
+            let id = reader.u32()?;
+            let (x, y, z) = (reader.f64()?, reader.f64()?, reader.f64()?);
+            coverage.node_map.insert(id, Node::new(x, y, z));
+        }
+        for _ in 0..reader.u32()? {
+            let id = reader.u32()?;
+            let face = Face::new(reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?);
+            for node_id in face.node_ids() {
+                coverage.node_face_adj.bind(node_id, id);
+            }
+            coverage.face_map.insert(id, face);
+        }
+        for _ in 0..reader.u32()? {
+            let id = reader.u32()?;
+            let mut values = [0; 6];
+            for value in &mut values {
+                *value = reader.u32()?;
+            }
+            let [start_id, end_id, face_id, prev_id, next_id, twin_id] = values;
+            if face_id > 0 {
+                coverage.face_half_edge_adj.bind(face_id, id);
+            }
+            coverage.half_edge_map.insert(
+                id,
+                HalfEdge::new(start_id, end_id, face_id, prev_id, next_id, twin_id),
+            );
+        }
+        coverage.node_id_generator.reserve(node_current);
+        coverage.face_id_generator.reserve(face_current);
+        coverage.half_edge_id_generator.reserve(half_edge_current);
+
+        for _ in 0..reader.u32()? {
+            let name = reader.string()?;
+            let location = match reader.u8()? {
+                0 => DatasetLocation::Node,
+                1 => DatasetLocation::Face,
+                other => return Err(invalid_data(&format!("unknown dataset location {}", other))),
+            };
+            let mut dataset = Dataset::new(name, location);
+            for _ in 0..reader.u32()? {
+                let time = reader.f64()?;
+                let kind = reader.u8()?;
+                let len = reader.u32()?;
+                let values = match kind {
+                    0 => {
+                        let mut map = HashMap::new();
+                        for _ in 0..len {
+                            map.insert(reader.u32()?, reader.f64()?);
+                        }
+                        DatasetValues::Scalar(map)
+                    }
+                    1 => {
+                        let mut map = HashMap::new();
+                        for _ in 0..len {
+                            map.insert(reader.u32()?, (reader.f64()?, reader.f64()?));
+                        }
+                        DatasetValues::Vector(map)
+                    }
+                    other => return Err(invalid_data(&format!("unknown dataset kind {}", other))),
+                };
+                dataset.add_step(time, values);
+            }
+            coverage.add_dataset(dataset);
+        }
+        if reader.offset != bytes.len() {
+            return Err(invalid_data("trailing bytes after snapshot"));
+        }
+
+        coverage.check_references()?;
+        coverage.rebuild_index();
+        Ok(coverage)
+    }
+
+    // 读取的面与半边引用的节点、面、半边必须存在，0 表示“无”；面至少有三个节点
+    fn check_references(&self) -> Result<()> {
+        for (id, face) in self.face_map.iter().collect::<BTreeMap<_, _>>() {
+            if face.n0 == 0 || face.n1 == 0 || face.n2 == 0 {
+                return Err(invalid_data(&format!("face {} has fewer than 3 nodes", id)));
+            }
+            if let Some(node_id) = face
+                .node_ids()
+                .into_iter()
+                .find(|node_id| !self.node_map.contains_key(node_id))
+            {
+                return Err(invalid_data(&format!(
+                    "face {} references missing node {}",
+                    id, node_id
+                )));
+            }
+        }
+        for (id, h) in self.half_edge_map.iter().collect::<BTreeMap<_, _>>() {
+            let missing = if !self.node_map.contains_key(&h.start_id) {
+                Some(("node", h.start_id))
+            } else if !self.node_map.contains_key(&h.end_id) {
+                Some(("node", h.end_id))
+            } else if h.face_id != 0 && !self.face_map.contains_key(&h.face_id) {
+                Some(("face", h.face_id))
+            } else {
+                [h.prev_id, h.next_id, h.twin_id]
+                    .into_iter()
+                    .find(|&other| other != 0 && !self.half_edge_map.contains_key(&other))
+                    .map(|other| ("half edge", other))
+            };
+            if let Some((kind, other)) = missing {
+                return Err(invalid_data(&format!(
+                    "half edge {} references missing {} {}",
+                    id, kind, other
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+struct SnapshotWriter {
+    bytes: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+}
+
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "snapshot truncated"))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|e| invalid_data(&e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: [&str; 2] = [
+        include_str!("fixtures/two_triangles.json"),
+        include_str!("fixtures/quad_and_triangle.json"),
+    ];
+
+    fn load(json: &str) -> MeshCoverage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn to_json(coverage: &MeshCoverage) -> String {
+        serde_json::to_string_pretty(coverage).unwrap() + "\n"
+    }
+
+    // 半边拓扑与重新生成的结果一致（按起点、终点、面比较，与半边 id 无关）
+    fn assert_half_edges_fresh(coverage: &MeshCoverage) {
+        let key = |c: &MeshCoverage, id: &u32| {
+            let h = c.half_edge_map[id];
+            (h.start_id, h.end_id, h.face_id)
+        };
+        let links = |c: &MeshCoverage| {
+            c.half_edge_map
+                .iter()
+                .map(|(id, h)| {
+                    (
+                        key(c, id),
+                        [h.prev_id, h.next_id, h.twin_id].map(|other| key(c, &other)),
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let mut fresh = coverage.clone();
+        fresh.generate_half_edges();
+        assert_eq!(links(coverage), links(&fresh));
+    }
+
+    #[test]
+    fn json_fixtures() {
+        for json in FIXTURES {
+            let coverage = load(json);
+            assert!(coverage.validate().is_valid());
+            assert_half_edges_fresh(&coverage);
+            assert_eq!(coverage.node_index.len(), coverage.node_map.len());
+            assert_eq!(coverage.face_index.len(), coverage.face_map.len());
+            assert!(!coverage.dataset_map.is_empty());
+            // 映射按 id 排序写出，重新序列化与夹具逐字相同
+            assert_eq!(to_json(&coverage), json.replace("\r\n", "\n"));
+        }
+    }
+
+    #[test]
+    fn fixture_contents() {
+        let coverage = load(FIXTURES[0]);
+        assert_eq!(coverage.node_map.len(), 4);
+        assert_eq!(coverage.face_map.len(), 2);
+        assert_eq!(coverage.half_edge_map.len(), 10);
+        let depth = coverage.get_dataset("depth").unwrap();
+        assert_eq!(depth.get_times(), vec![0.0, 3600.0]);
+        assert_eq!(depth.get_value(1, 4), Some(1.75));
+
+        let coverage = load(FIXTURES[1]);
+        assert!(!coverage.face_map[&1].is_triangle());
+        assert!(coverage.face_map[&2].is_triangle());
+        assert!(coverage.get_dataset("velocity").unwrap().is_vector());
+        assert_eq!(coverage.get_boundary().outer_loops[0].len(), 5);
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        for json in FIXTURES {
+            let coverage = load(json);
+            let bytes = coverage.to_snapshot();
+            let loaded = MeshCoverage::from_snapshot(&bytes).unwrap();
+            assert_eq!(loaded.to_snapshot(), bytes);
+            assert_eq!(to_json(&loaded), to_json(&coverage));
+            assert_half_edges_fresh(&loaded);
+
+            // 读取后继续编辑，新 id 不与已有的冲突
+            let mut edited = loaded;
+            let node_id = edited.create_node(5.0, 5.0, 0.0);
+            assert!(!coverage.node_map.contains_key(&node_id));
+        }
+    }
+
+    #[test]
+    fn reject_malformed_snapshots() {
+        let bytes = load(FIXTURES[1]).to_snapshot();
+        for len in 0..bytes.len() {
+            assert!(
+                MeshCoverage::from_snapshot(&bytes[..len]).is_err(),
+                "{}",
+                len
+            );
+        }
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        let error = MeshCoverage::from_snapshot(&bad_magic).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        let mut bad_version = bytes.clone();
+        bad_version[MAGIC.len()] = 99;
+        assert!(MeshCoverage::from_snapshot(&bad_version).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let error = MeshCoverage::from_snapshot(&trailing).unwrap_err();
+        assert_eq!(error.to_string(), "trailing bytes after snapshot");
+    }
+
+    #[test]
+    fn reject_dangling_references() {
+        let mut coverage = load(FIXTURES[0]);
+        coverage.create_face(1, 2, 999, 0);
+        let error = MeshCoverage::from_snapshot(&coverage.to_snapshot()).unwrap_err();
+        assert_eq!(error.to_string(), "face 3 references missing node 999");
+        assert!(serde_json::from_str::<MeshCoverage>(&to_json(&coverage)).is_err());
+
+        let mut coverage = load(FIXTURES[0]);
+        coverage.half_edge_map.get_mut(&1).unwrap().twin_id = 100;
+        let error = MeshCoverage::from_snapshot(&coverage.to_snapshot()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "half edge 1 references missing half edge 100"
+        );
+
+        let mut coverage = load(FIXTURES[0]);
+        coverage.half_edge_map.get_mut(&1).unwrap().face_id = 7;
+        assert!(MeshCoverage::from_snapshot(&coverage.to_snapshot()).is_err());
+        assert!(serde_json::from_str::<MeshCoverage>(&to_json(&coverage)).is_err());
+    }
+}
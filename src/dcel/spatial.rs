@@ -0,0 +1,659 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use super::{geometry::point_in_polygon, MeshCoverage};
+
+// 节点内的条目超过该数量时拆分
+const NODE_CAPACITY: usize = 16;
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Rect {
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x: min_x.min(max_x),
+            min_y: min_y.min(max_y),
+            max_x: min_x.max(max_x),
+            max_y: min_y.max(max_y),
+        }
+    }
+
+    pub fn point(x: f64, y: f64) -> Self {
+        Self::new(x, y, x, y)
+    }
+
+    pub fn from_points(points: &[(f64, f64)]) -> Option<Self> {
+        let (&(x, y), rest) = points.split_first()?;
+        let mut rect = Self::point(x, y);
+        for &(x, y) in rest {
+            rect.min_x = rect.min_x.min(x);
+            rect.min_y = rect.min_y.min(y);
+            rect.max_x = rect.max_x.max(x);
+            rect.max_y = rect.max_y.max(y);
+        }
+        Some(rect)
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min_x.is_finite()
+            && self.min_y.is_finite()
+            && self.max_x.is_finite()
+            && self.max_y.is_finite()
+    }
+
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.min_x >= self.min_x
+            && other.max_x <= self.max_x
+            && other.min_y >= self.min_y
+            && other.max_y <= self.max_y
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    // 点到矩形的距离平方，点在矩形内为 0
+    pub fn distance2(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.min_x - x).max(0.0).max(x - self.max_x);
+        let dy = (self.min_y - y).max(0.0).max(y - self.max_y);
+        dx * dx + dy * dy
+    }
+
+    // 线段 pq 是否与矩形相交（Liang-Barsky 裁剪）
+    pub fn intersects_segment(&self, p: (f64, f64), q: (f64, f64)) -> bool {
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let mut t0: f64 = 0.0;
+        let mut t1: f64 = 1.0;
+        for (d, lower, upper) in [
+            (dx, self.min_x - p.0, self.max_x - p.0),
+            (dy, self.min_y - p.1, self.max_y - p.1),
+        ] {
+            if d == 0.0 {
+                if lower > 0.0 || upper < 0.0 {
+                    return false;
+                }
+            } else {
+                let (a, b) = (lower / d, upper / d);
+                t0 = t0.max(a.min(b));
+                t1 = t1.min(a.max(b));
+                if t0 > t1 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (
+            (self.min_x + self.max_x) / 2.0,
+            (self.min_y + self.max_y) / 2.0,
+        )
+    }
+
+    // 各方向向外扩展 ratio 倍宽高
+    fn expand(&self, ratio: f64) -> Rect {
+        let dx = (self.max_x - self.min_x) * ratio;
+        let dy = (self.max_y - self.min_y) * ratio;
+        Rect {
+            min_x: self.min_x - dx,
+            min_y: self.min_y - dy,
+            max_x: self.max_x + dx,
+            max_y: self.max_y + dy,
+        }
+    }
+
+    fn quadrant(&self, i: usize) -> Rect {
+        let mid_x = (self.min_x + self.max_x) / 2.0;
+        let mid_y = (self.min_y + self.max_y) / 2.0;
+        let (min_x, max_x) = if i & 1 == 0 {
+            (self.min_x, mid_x)
+        } else {
+            (mid_x, self.max_x)
+        };
+        let (min_y, max_y) = if i / 2 == 0 {
+            (self.min_y, mid_y)
+        } else {
+            (mid_y, self.max_y)
+        };
+        Rect {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+}
+
+// 松散四叉树节点：条目按中心点所在象限下放，只要求落在象限向外扩展半个宽高的松散范围内，
+// 避免跨越象限分界线的小面都堆积在上层节点
+#[derive(Debug, Clone)]
+struct QuadNode {
+    bounds: Rect,
+    loose: Rect,
+    // 不能放入任何子节点的条目
+    items: Vec<(u32, Rect)>,
+    // 按 西南、东南、西北、东北 排列
+    children: Option<Box<[QuadNode; 4]>>,
+}
+
+impl QuadNode {
+    fn new(bounds: Rect) -> Self {
+        Self {
+            bounds,
+            loose: bounds.expand(0.5),
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn accepts(&self, rect: &Rect) -> bool {
+        let (x, y) = rect.center();
+        self.bounds.contains_point(x, y) && self.loose.contains(rect)
+    }
+
+    fn insert(&mut self, id: u32, rect: Rect, depth: usize) {
+        if let Some(children) = self.children.as_mut() {
+            if let Some(child) = children.iter_mut().find(|c| c.accepts(&rect)) {
+                return child.insert(id, rect, depth + 1);
+            }
+            self.items.push((id, rect));
+            return;
+        }
+        self.items.push((id, rect));
+        if self.items.len() > NODE_CAPACITY && depth < MAX_DEPTH {
+            let bounds = self.bounds;
+            self.children = Some(Box::new(
+                [0, 1, 2, 3].map(|i| QuadNode::new(bounds.quadrant(i))),
+            ));
+            let items = std::mem::take(&mut self.items);
+            for (item, rect) in items {
+                self.insert(item, rect, depth);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u32, rect: &Rect) -> bool {
+        if let Some(i) = self.items.iter().position(|&(item, _)| item == id) {
+            self.items.swap_remove(i);
+            return true;
+        }
+        if let Some(children) = self.children.as_mut() {
+            for child in children.iter_mut() {
+                if child.accepts(rect) && child.remove(id, rect) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn query<F: Fn(&Rect) -> bool>(&self, test: &F, result: &mut Vec<u32>) {
+        if !test(&self.loose) {
+            return;
+        }
+        result.extend(
+            self.items
+                .iter()
+                .filter(|(_, rect)| test(rect))
+                .map(|(id, _)| *id),
+        );
+        if let Some(children) = self.children.as_ref() {
+            for child in children.iter() {
+                child.query(test, result);
+            }
+        }
+    }
+}
+
+// 最近点搜索的候选：四叉树节点或条目，按距离从小到大出堆
+enum Candidate<'a> {
+    Node(&'a QuadNode),
+    Item(u32),
+}
+
+struct Entry<'a>(f64, Candidate<'a>);
+
+impl PartialEq for Entry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Entry<'_> {}
+
+impl PartialOrd for Entry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+// 存放矩形的四叉树，超出范围时根节点向外成倍扩展
+#[derive(Debug, Clone, Default)]
+pub struct QuadTree {
+    root: Option<QuadNode>,
+    rects: HashMap<u32, Rect>,
+}
+
+impl QuadTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<Rect> {
+        self.rects.get(&id).copied()
+    }
+
+    // 插入或更新条目的矩形
+    pub fn insert(&mut self, id: u32, rect: Rect) {
+        self.remove(id);
+        if !rect.is_finite() {
+            return;
+        }
+        let root = self.root.get_or_insert_with(|| {
+            let size = (rect.max_x - rect.min_x)
+                .max(rect.max_y - rect.min_y)
+                .max(1.0);
+            let (c_x, c_y) = (
+                (rect.min_x + rect.max_x) / 2.0,
+                (rect.min_y + rect.max_y) / 2.0,
+            );
+            QuadNode::new(Rect::new(c_x - size, c_y - size, c_x + size, c_y + size))
+        });
+        while !root.bounds.contains(&rect) {
+            // 向条目所在方向扩展一倍，旧根节点成为新根节点的一个象限
+            let b = root.bounds;
+            let mut old = std::mem::replace(root, QuadNode::new(b));
+            let size = b.max_x - b.min_x;
+            let east = rect.min_x >= b.min_x;
+            let north = rect.min_y >= b.min_y;
+            let (min_x, max_x) = if east {
+                (b.min_x, b.max_x + size)
+            } else {
+                (b.min_x - size, b.max_x)
+            };
+            let (min_y, max_y) = if north {
+                (b.min_y, b.max_y + size)
+            } else {
+                (b.min_y - size, b.max_y)
+            };
+            let bounds = Rect::new(min_x, min_y, max_x, max_y);
+            let index = if east { 0 } else { 1 } + if north { 0 } else { 2 };
+            let mut children = [0, 1, 2, 3].map(|i| QuadNode::new(bounds.quadrant(i)));
+            // 旧根节点的条目中心可能不在其象限内，重新插入
+            let items = std::mem::take(&mut old.items);
+            children[index] = old;
+            *root = QuadNode::new(bounds);
+            root.children = Some(Box::new(children));
+            for (item, item_rect) in items {
+                root.insert(item, item_rect, 0);
+            }
+        }
+        self.rects.insert(id, rect);
+        root.insert(id, rect, 0);
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        match (self.rects.remove(&id), self.root.as_mut()) {
+            (Some(rect), Some(root)) => root.remove(id, &rect),
+            _ => false,
+        }
+    }
+
+    // 矩形与 rect 相交的条目
+    pub fn query_rect(&self, rect: &Rect) -> Vec<u32> {
+        let mut result = Vec::new();
+        if let Some(root) = self.root.as_ref() {
+            root.query(&|r: &Rect| r.intersects(rect), &mut result);
+        }
+        result
+    }
+
+    // 矩形与线段 pq 相交的条目
+    pub fn query_segment(&self, p: (f64, f64), q: (f64, f64)) -> Vec<u32> {
+        let mut result = Vec::new();
+        if let Some(root) = self.root.as_ref() {
+            root.query(&|r: &Rect| r.intersects_segment(p, q), &mut result);
+        }
+        result
+    }
+
+    // 矩形距 (x, y) 最近的条目，距离相同时返回先找到的
+    pub fn nearest(&self, x: f64, y: f64) -> Option<u32> {
+        let mut heap: BinaryHeap<Entry> = BinaryHeap::new();
+        if let Some(root) = self.root.as_ref() {
+            heap.push(Entry(root.loose.distance2(x, y), Candidate::Node(root)));
+        }
+        while let Some(Entry(_, candidate)) = heap.pop() {
+            match candidate {
+                Candidate::Item(id) => return Some(id),
+                Candidate::Node(node) => {
+                    for (id, rect) in &node.items {
+                        heap.push(Entry(rect.distance2(x, y), Candidate::Item(*id)));
+                    }
+                    if let Some(children) = node.children.as_ref() {
+                        for child in children.iter() {
+                            heap.push(Entry(child.loose.distance2(x, y), Candidate::Node(child)));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn segments_intersect(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> Option<f64> {
+    let r = (b.0 - a.0, b.1 - a.1);
+    let s = (d.0 - c.0, d.1 - c.1);
+    let denominator = r.0 * s.1 - r.1 * s.0;
+    let (qx, qy) = (c.0 - a.0, c.1 - a.1);
+    if denominator == 0.0 {
+        // 平行：共线且有重叠时取重叠部分的起点
+        if qx * r.1 - qy * r.0 != 0.0 {
+            return None;
+        }
+        let len2 = r.0 * r.0 + r.1 * r.1;
+        if len2 == 0.0 {
+            return None;
+        }
+        let t0 = (qx * r.0 + qy * r.1) / len2;
+        let t1 = ((d.0 - a.0) * r.0 + (d.1 - a.1) * r.1) / len2;
+        let (t0, t1) = (t0.min(t1), t0.max(t1));
+        return if t1 < 0.0 || t0 > 1.0 {
+            None
+        } else {
+            Some(t0.max(0.0))
+        };
+    }
+    let t = (qx * s.1 - qy * s.0) / denominator;
+    let u = (qx * r.1 - qy * r.0) / denominator;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+impl MeshCoverage {
+    // 由节点与面重新生成空间索引，如反序列化之后
+    pub fn rebuild_index(&mut self) {
+        self.node_index = QuadTree::new();
+        for (&id, node) in &self.node_map {
+            self.node_index.insert(id, Rect::point(node.x, node.y));
+        }
+        self.face_index = QuadTree::new();
+        let face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        for id in face_ids {
+            if let Some(rect) = self.get_face_rect(id) {
+                self.face_index.insert(id, rect);
+            }
+        }
+    }
+
+    pub fn find_nearest_node(&self, x: f64, y: f64) -> Option<u32> {
+        self.node_index.nearest(x, y)
+    }
+
+    pub fn find_nodes_in_rect(&self, rect: &Rect) -> Vec<u32> {
+        let mut result = self.node_index.query_rect(rect);
+        result.sort();
+        result
+    }
+
+    // 与矩形有重叠的面，引用了不存在的节点的面不计入
+    pub fn find_faces_in_rect(&self, rect: &Rect) -> Vec<u32> {
+        let corners = [
+            (rect.min_x, rect.min_y),
+            (rect.max_x, rect.min_y),
+            (rect.max_x, rect.max_y),
+            (rect.min_x, rect.max_y),
+        ];
+        let mut result: Vec<u32> = self
+            .face_index
+            .query_rect(rect)
+            .into_iter()
+            .filter(|face_id| {
+                let ids = self.face_map[face_id].node_ids();
+                if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                    return false;
+                }
+                let points = self.get_face_points(&ids);
+                let len = points.len();
+                points.iter().any(|p| rect.contains_point(p.0, p.1))
+                    || corners.iter().any(|c| point_in_polygon(*c, &points))
+                    || (0..len).any(|i| rect.intersects_segment(points[i], points[(i + 1) % len]))
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    // 线段 pq 经过的面，按进入面的先后排序，引用了不存在的节点的面不计入
+    pub fn find_faces_along_segment(&self, p: (f64, f64), q: (f64, f64)) -> Vec<u32> {
+        let mut result: Vec<(f64, u32)> = Vec::new();
+        for face_id in self.face_index.query_segment(p, q) {
+            let ids = self.face_map[&face_id].node_ids();
+            if !ids.iter().all(|id| self.node_map.contains_key(id)) {
+                continue;
+            }
+            let points = self.get_face_points(&ids);
+            let len = points.len();
+            let enter = if point_in_polygon(p, &points) {
+                Some(0.0)
+            } else {
+                (0..len)
+                    .filter_map(|i| segments_intersect(p, q, points[i], points[(i + 1) % len]))
+                    .min_by(|a, b| a.total_cmp(b))
+            };
+            if let Some(t) = enter {
+                result.push((t, face_id));
+            }
+        }
+        result.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        result.into_iter().map(|(_, id)| id).collect()
+    }
+
+    pub fn get_face_rect(&self, face_id: u32) -> Option<Rect> {
+        let ids = self.face_map.get(&face_id)?.node_ids();
+        let points: Vec<(f64, f64)> = ids
+            .iter()
+            .filter_map(|id| self.node_map.get(id).map(|node| (node.x, node.y)))
+            .collect();
+        Rect::from_points(&points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 线性同余随机数，保证结果可重复
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> f64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 11) as f64 / (1u64 << 53) as f64
+        }
+
+        fn rect(&mut self, extent: f64, size: f64) -> Rect {
+            let (x, y) = (self.next() * extent, self.next() * extent);
+            Rect::new(x, y, x + self.next() * size, y + self.next() * size)
+        }
+    }
+
+    fn sorted(mut ids: Vec<u32>) -> Vec<u32> {
+        ids.sort();
+        ids
+    }
+
+    // 插入、更新、删除后与逐个比较的结果一致，包括远处条目引起的根节点扩展
+    #[test]
+    fn queries_match_brute_force() {
+        let mut random = Random(7);
+        let mut tree = QuadTree::new();
+        let mut rects: HashMap<u32, Rect> = HashMap::new();
+        for id in 1..=2000 {
+            let extent = if id % 97 == 0 { 1e5 } else { 100.0 };
+            let rect = random.rect(extent, 2.0);
+            tree.insert(id, rect);
+            rects.insert(id, rect);
+        }
+        tree.insert(5000, Rect::point(-3e4, -7e4));
+        rects.insert(5000, Rect::point(-3e4, -7e4));
+        for id in (1..=2000).step_by(3) {
+            assert!(tree.remove(id));
+            rects.remove(&id);
+        }
+        assert!(!tree.remove(1));
+        for id in (2..=2000).step_by(5) {
+            let rect = random.rect(100.0, 5.0);
+            tree.insert(id, rect);
+            rects.insert(id, rect);
+        }
+        assert_eq!(tree.len(), rects.len());
+
+        for _ in 0..200 {
+            let query = random.rect(110.0, 20.0);
+            let expected: Vec<u32> = rects
+                .iter()
+                .filter(|(_, r)| r.intersects(&query))
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(sorted(tree.query_rect(&query)), sorted(expected));
+
+            let (p, q) = (
+                (random.next() * 120.0 - 10.0, random.next() * 120.0 - 10.0),
+                (random.next() * 120.0 - 10.0, random.next() * 120.0 - 10.0),
+            );
+            let expected: Vec<u32> = rects
+                .iter()
+                .filter(|(_, r)| r.intersects_segment(p, q))
+                .map(|(id, _)| *id)
+                .collect();
+            assert_eq!(sorted(tree.query_segment(p, q)), sorted(expected));
+
+            let (x, y) = (random.next() * 200.0 - 50.0, random.next() * 200.0 - 50.0);
+            let nearest = tree.nearest(x, y).unwrap();
+            let min = rects
+                .values()
+                .map(|r| r.distance2(x, y))
+                .fold(f64::MAX, f64::min);
+            assert_eq!(rects[&nearest].distance2(x, y), min);
+        }
+    }
+
+    #[test]
+    fn empty_and_invalid() {
+        let mut tree = QuadTree::new();
+        assert!(tree.nearest(0.0, 0.0).is_none());
+        assert!(tree.query_rect(&Rect::new(-1.0, -1.0, 1.0, 1.0)).is_empty());
+        tree.insert(1, Rect::point(f64::NAN, 0.0));
+        assert!(tree.is_empty());
+        tree.insert(2, Rect::point(0.0, 0.0));
+        tree.insert(2, Rect::point(50.0, 50.0));
+        assert_eq!(tree.len(), 1);
+        assert!(tree.query_rect(&Rect::new(-1.0, -1.0, 1.0, 1.0)).is_empty());
+        assert_eq!(tree.nearest(0.0, 0.0), Some(2));
+    }
+
+    // 建网、移动节点、删除后索引与网格保持一致
+    #[test]
+    fn mesh_index_follows_edits() {
+        let mut coverage = MeshCoverage::new("a".into());
+        let mut ids = Vec::new();
+        for j in 0..=4 {
+            for i in 0..=4 {
+                ids.push(coverage.create_node(i as f64, j as f64, 0.0));
+            }
+        }
+        for j in 0..4 {
+            for i in 0..4 {
+                let n = j * 5 + i;
+                coverage.create_face(ids[n], ids[n + 1], ids[n + 6], ids[n + 5]);
+            }
+        }
+        coverage.generate_half_edges();
+        assert_eq!(coverage.node_index.len(), 25);
+        assert_eq!(coverage.face_index.len(), 16);
+        assert_eq!(coverage.find_nearest_node(2.2, 2.9), Some(ids[17]));
+        assert_eq!(
+            coverage
+                .find_faces_in_rect(&Rect::new(0.5, 0.5, 1.5, 0.6))
+                .len(),
+            2
+        );
+        assert_eq!(
+            coverage
+                .find_faces_along_segment((0.5, 0.5), (3.5, 0.5))
+                .len(),
+            4
+        );
+
+        coverage.set_node_position(ids[12], 2.4, 2.4, 0.0);
+        assert_eq!(coverage.find_nearest_node(2.45, 2.45), Some(ids[12]));
+        assert!(coverage
+            .find_nodes_in_rect(&Rect::new(1.9, 1.9, 2.1, 2.1))
+            .is_empty());
+
+        coverage.remove_node(ids[0]);
+        assert_eq!(coverage.node_index.len(), 24);
+        assert_eq!(coverage.face_index.len(), 15);
+        assert!(coverage
+            .find_faces_in_rect(&Rect::new(0.2, 0.2, 0.3, 0.3))
+            .is_empty());
+    }
+
+    #[test]
+    fn faces_with_missing_nodes() {
+        let mut coverage = MeshCoverage::new("a".into());
+        let a = coverage.create_node(0.0, 0.0, 0.0);
+        let b = coverage.create_node(1.0, 0.0, 0.0);
+        let c = coverage.create_node(1.0, 1.0, 0.0);
+        let face_id = coverage.create_face(a, b, c, 0);
+        coverage.create_face(b, 99, c, 0);
+        coverage.generate_half_edges();
+        assert_eq!(
+            coverage.find_faces_in_rect(&Rect::new(-1.0, -1.0, 2.0, 2.0)),
+            vec![face_id]
+        );
+        assert_eq!(
+            coverage.find_faces_along_segment((0.9, -1.0), (0.9, 2.0)),
+            vec![face_id]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use super::{dataset::DatasetValues, MeshCoverage};
+
+// (原坐标, 目标坐标)
+pub type PointPair = ((f64, f64), (f64, f64));
+
+// 平面仿射变换：x' = a * x + b * y + c，y' = d * x + e * y + f
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Affine {
+    pub fn identity() -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 0.0,
+            e: 1.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn translation(dx: f64, dy: f64) -> Self {
+        Self {
+            c: dx,
+            f: dy,
+            ..Self::identity()
+        }
+    }
+
+    // 绕 center 逆时针旋转，单位为度
+    pub fn rotation(angle: f64, center: (f64, f64)) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let linear = Self {
+            a: cos,
+            b: -sin,
+            c: 0.0,
+            d: sin,
+            e: cos,
+            f: 0.0,
+        };
+        Self::about(linear, center)
+    }
+
+    // 以 center 为中心缩放
+    pub fn scale(sx: f64, sy: f64, center: (f64, f64)) -> Self {
+        let linear = Self {
+            a: sx,
+            e: sy,
+            ..Self::identity()
+        };
+        Self::about(linear, center)
+    }
+
+    // 先平移到 center 为原点，变换后再移回
+    fn about(linear: Self, center: (f64, f64)) -> Self {
+        Self::translation(-center.0, -center.1)
+            .then(&linear)
+            .then(&Self::translation(center.0, center.1))
+    }
+
+    // 先应用 self 再应用 other
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            a: other.a * self.a + other.b * self.d,
+            b: other.a * self.b + other.b * self.e,
+            c: other.a * self.c + other.b * self.f + other.c,
+            d: other.d * self.a + other.e * self.d,
+            e: other.d * self.b + other.e * self.e,
+            f: other.d * self.c + other.e * self.f + other.f,
+        }
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.b * y + self.c,
+            self.d * x + self.e * y + self.f,
+        )
+    }
+
+    // 只应用线性部分，用于矢量
+    pub fn apply_vector(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.a * x + self.b * y, self.d * x + self.e * y)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.e - self.b * self.d
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (a, b, d, e) = (self.e / det, -self.b / det, -self.d / det, self.a / det);
+        Some(Self {
+            a,
+            b,
+            c: -(a * self.c + b * self.f),
+            d,
+            e,
+            f: -(d * self.c + e * self.f),
+        })
+    }
+
+    // 由控制点对求变换：一对时为平移，两对时为相似变换（平移、旋转及等比缩放），
+    // 三对及以上时为最小二乘的仿射变换；控制点重合或共线时为 None
+    pub fn from_point_pairs(pairs: &[PointPair]) -> Option<Self> {
+        let n = pairs.len() as f64;
+        let (p, q) = match pairs {
+            [] => return None,
+            [(p, q)] => return Some(Self::translation(q.0 - p.0, q.1 - p.1)),
+            _ => (
+                (
+                    pairs.iter().map(|(p, _)| p.0).sum::<f64>() / n,
+                    pairs.iter().map(|(p, _)| p.1).sum::<f64>() / n,
+                ),
+                (
+                    pairs.iter().map(|(_, q)| q.0).sum::<f64>() / n,
+                    pairs.iter().map(|(_, q)| q.1).sum::<f64>() / n,
+                ),
+            ),
+        };
+        // 以重心为原点的二阶矩
+        let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+        let (mut sxu, mut syu, mut sxv, mut syv) = (0.0, 0.0, 0.0, 0.0);
+        for ((x, y), (u, v)) in pairs {
+            let (x, y, u, v) = (x - p.0, y - p.1, u - q.0, v - q.1);
+            sxx += x * x;
+            sxy += x * y;
+            syy += y * y;
+            sxu += x * u;
+            syu += y * u;
+            sxv += x * v;
+            syv += y * v;
+        }
+
+        let linear = if pairs.len() == 2 {
+            // x' = a x - b y，y' = b x + a y
+            let norm = sxx + syy;
+            if norm == 0.0 {
+                return None;
+            }
+            let a = (sxu + syv) / norm;
+            let b = (sxv - syu) / norm;
+            Self {
+                a,
+                b: -b,
+                c: 0.0,
+                d: b,
+                e: a,
+                f: 0.0,
+            }
+        } else {
+            let det = sxx * syy - sxy * sxy;
+            if det.abs() <= f64::EPSILON * (sxx * syy).max(f64::MIN_POSITIVE) {
+                return None;
+            }
+            Self {
+                a: (sxu * syy - syu * sxy) / det,
+                b: (syu * sxx - sxu * sxy) / det,
+                c: 0.0,
+                d: (sxv * syy - syv * sxy) / det,
+                e: (syv * sxx - sxv * sxy) / det,
+                f: 0.0,
+            }
+        };
+        Some(
+            Self::translation(-p.0, -p.1)
+                .then(&linear)
+                .then(&Self::translation(q.0, q.1)),
+        )
+    }
+}
+
+impl MeshCoverage {
+    // 变换所有节点的平面坐标，矢量数据集按线性部分一起变换；
+    // 行列式为负（镜像）时反转面的节点顺序以保持绕向，并重新生成半边拓扑
+    pub fn transform(&mut self, affine: &Affine) {
+        for node in self.node_map.values_mut() {
+            let (x, y) = affine.apply(node.x, node.y);
+            node.x = x;
+            node.y = y;
+        }
+        for p in &mut self.decimated_points {
+            let (x, y) = affine.apply(p[0], p[1]);
+            p[0] = x;
+            p[1] = y;
+        }
+        for dataset in self.dataset_map.values_mut() {
+            for step in &mut dataset.steps {
+                if let DatasetValues::Vector(map) = &mut step.values {
+                    for value in map.values_mut() {
+                        *value = affine.apply_vector(value.0, value.1);
+                    }
+                }
+            }
+        }
+        if affine.determinant() < 0.0 {
+            for face in self.face_map.values_mut() {
+                if face.n3 > 0 {
+                    *face = super::Face::new(face.n0, face.n3, face.n2, face.n1);
+                } else {
+                    *face = super::Face::new(face.n0, face.n2, face.n1, 0);
+                }
+            }
+            self.generate_half_edges();
+        }
+        self.rebuild_index();
+    }
+
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        self.transform(&Affine::translation(dx, dy));
+    }
+
+    pub fn rotate(&mut self, angle: f64, center: (f64, f64)) {
+        self.transform(&Affine::rotation(angle, center));
+    }
+
+    pub fn scale(&mut self, sx: f64, sy: f64, center: (f64, f64)) {
+        self.transform(&Affine::scale(sx, sy, center));
+    }
+
+    // z' = z * scale + offset
+    pub fn transform_z(&mut self, scale: f64, offset: f64) {
+        for node in self.node_map.values_mut() {
+            node.z = node.z * scale + offset;
+        }
+        for p in &mut self.decimated_points {
+            p[2] = p[2] * scale + offset;
+        }
+    }
+
+    pub fn offset_z(&mut self, offset: f64) {
+        self.transform_z(1.0, offset);
+    }
+
+    pub fn scale_z(&mut self, scale: f64) {
+        self.transform_z(scale, 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+use super::MeshCoverage;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    // 沿 next 走完一个面或一个边界环
+    Next,
+    // 绕起点旋转：twin 的 next 仍从同一节点出发
+    Rotate,
+}
+
+// 沿半边指针遍历，回到起始半边时结束；指针损坏时最多走 half_edge_map.len() 步
+#[derive(Debug, Clone)]
+pub struct HalfEdgeIter<'a> {
+    mesh: &'a MeshCoverage,
+    step: Step,
+    start: u32,
+    current: u32,
+    remaining: usize,
+}
+
+impl<'a> HalfEdgeIter<'a> {
+    fn new(mesh: &'a MeshCoverage, start: u32, step: Step) -> Self {
+        let start = if mesh.half_edge_map.contains_key(&start) {
+            start
+        } else {
+            0
+        };
+        Self {
+            mesh,
+            step,
+            start,
+            current: start,
+            remaining: mesh.half_edge_map.len(),
+        }
+    }
+}
+
+impl Iterator for HalfEdgeIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.current == 0 || self.remaining == 0 {
+            return None;
+        }
+        let id = self.current;
+        let half_edge = self.mesh.half_edge_map.get(&id)?;
+        let following = match self.step {
+            Step::Next => half_edge.next_id,
+            Step::Rotate => self
+                .mesh
+                .half_edge_map
+                .get(&half_edge.twin_id)
+                .map_or(0, |twin| twin.next_id),
+        };
+        self.remaining -= 1;
+        self.current = if following == self.start {
+            0
+        } else {
+            following
+        };
+        Some(id)
+    }
+}
+
+impl MeshCoverage {
+    // 从节点出发的半边，包括边界半边；面为逆时针时按顺时针顺序，非流形节点只遍历起始半边所在的扇区
+    pub fn iter_node_half_edges(&self, node_id: u32) -> HalfEdgeIter<'_> {
+        HalfEdgeIter::new(
+            self,
+            self.find_half_edge_from(node_id).unwrap_or(0),
+            Step::Rotate,
+        )
+    }
+
+    // 一环相邻节点，与 iter_node_half_edges 顺序一致
+    pub fn iter_node_neighbours(&self, node_id: u32) -> impl Iterator<Item = u32> + '_ {
+        self.iter_node_half_edges(node_id)
+            .map(|id| self.half_edge_map[&id].end_id)
+    }
+
+    // 节点周围的面，跳过边界处的缺口
+    pub fn iter_node_faces(&self, node_id: u32) -> impl Iterator<Item = u32> + '_ {
+        self.iter_node_half_edges(node_id)
+            .map(|id| self.half_edge_map[&id].face_id)
+            .filter(|&face_id| face_id > 0)
+    }
+
+    // 面上的半边，从 n0 出发的半边开始按面的节点顺序排列
+    pub fn iter_face_half_edges(&self, face_id: u32) -> HalfEdgeIter<'_> {
+        let start = self
+            .face_map
+            .get(&face_id)
+            .and_then(|face| {
+                self.face_half_edge_adj
+                    .get_face_adj_half_edges(face_id)?
+                    .iter()
+                    .find(|id| self.half_edge_map[*id].start_id == face.n0)
+                    .copied()
+            })
+            .unwrap_or(0);
+        HalfEdgeIter::new(self, start, Step::Next)
+    }
+
+    // 隔着面上每条边的相邻面，与 iter_face_half_edges 一一对应，边界边为 0
+    pub fn iter_face_neighbours(&self, face_id: u32) -> impl Iterator<Item = u32> + '_ {
+        self.iter_face_half_edges(face_id).map(|id| {
+            self.half_edge_map
+                .get(&self.half_edge_map[&id].twin_id)
+                .map_or(0, |twin| twin.face_id)
+        })
+    }
+
+    // 从一条边界半边出发走完所在的边界环，不是边界半边时为空
+    pub fn iter_boundary_loop(&self, half_edge_id: u32) -> HalfEdgeIter<'_> {
+        let start = if self.is_boundary_half_edge(half_edge_id) {
+            half_edge_id
+        } else {
+            0
+        };
+        HalfEdgeIter::new(self, start, Step::Next)
+    }
+
+    // 从节点出发的任意一条半边，优先取 id 最小的相邻面上的
+    fn find_half_edge_from(&self, node_id: u32) -> Option<u32> {
+        let face_id = *self
+            .node_face_adj
+            .get_node_adj_faces(node_id)?
+            .iter()
+            .min()?;
+        self.face_half_edge_adj
+            .get_face_adj_half_edges(face_id)?
+            .iter()
+            .find(|id| self.half_edge_map[*id].start_id == node_id)
+            .copied()
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use super::{geometry::is_zero_area, MeshCoverage};
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    // 引用了不存在节点的面：(面 id, 缺失的节点 id)
+    pub missing_node_faces: Vec<(u32, Vec<u32>)>,
+    // 重复节点或面积为零的面
+    pub degenerate_faces: Vec<u32>,
+    // 节点集合完全相同的面，每组按 id 升序
+    pub duplicate_faces: Vec<Vec<u32>>,
+    // 被两个以上面共享的边：(n0, n1, 面 id)
+    pub non_manifold_edges: Vec<(u32, u32, Vec<u32>)>,
+    // 以相同方向经过公共边的相邻面
+    pub inconsistent_winding: Vec<(u32, u32)>,
+    // 不属于任何面的节点
+    pub orphan_nodes: Vec<u32>,
+    // 相邻面在该点处不连通的节点（蝴蝶结点）
+    pub bow_tie_nodes: Vec<u32>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing_node_faces.is_empty()
+            && self.degenerate_faces.is_empty()
+            && self.duplicate_faces.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.inconsistent_winding.is_empty()
+            && self.orphan_nodes.is_empty()
+            && self.bow_tie_nodes.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "mesh topology ok.");
+        }
+        writeln!(f, "mesh topology problems:")?;
+        writeln!(f, "  missing node faces: {}", self.missing_node_faces.len())?;
+        writeln!(f, "  degenerate faces: {}", self.degenerate_faces.len())?;
+        writeln!(f, "  duplicate faces: {}", self.duplicate_faces.len())?;
+        writeln!(f, "  non-manifold edges: {}", self.non_manifold_edges.len())?;
+        writeln!(
+            f,
+            "  inconsistent winding: {}",
+            self.inconsistent_winding.len()
+        )?;
+        writeln!(f, "  orphan nodes: {}", self.orphan_nodes.len())?;
+        write!(f, "  bow-tie nodes: {}", self.bow_tie_nodes.len())
+    }
+}
+
+impl MeshCoverage {
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let mut face_ids: Vec<u32> = self.face_map.keys().copied().collect();
+        face_ids.sort();
+
+        // 无向边 -> [(面 id, 是否与 (min, max) 同向)]
+        let mut edge_faces: HashMap<(u32, u32), Vec<(u32, bool)>> = HashMap::new();
+        let mut face_keys: HashMap<Vec<u32>, Vec<u32>> = HashMap::new();
+        let mut used_nodes: HashSet<u32> = HashSet::new();
+
+        for &face_id in &face_ids {
+            let ids = self.face_map[&face_id].node_ids();
+
+            let missing: Vec<u32> = ids
+                .iter()
+                .filter(|id| !self.node_map.contains_key(id))
+                .copied()
+                .collect();
+            if !missing.is_empty() {
+                report.missing_node_faces.push((face_id, missing));
+                continue;
+            }
+            used_nodes.extend(ids.iter().copied());
+
+            let unique: HashSet<u32> = ids.iter().copied().collect();
+            if unique.len() < ids.len() || is_zero_area(&self.get_face_points(&ids)) {
+                report.degenerate_faces.push(face_id);
+                continue;
+            }
+
+            let mut key = ids.clone();
+            key.sort();
+            face_keys.entry(key).or_default().push(face_id);
+
+            for i in 0..ids.len() {
+                let a = ids[i];
+                let b = ids[(i + 1) % ids.len()];
+                edge_faces
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((face_id, a < b));
+            }
+        }
+
+        for (_key, faces) in face_keys {
+            if faces.len() > 1 {
+                report.duplicate_faces.push(faces);
+            }
+        }
+        report.duplicate_faces.sort();
+
+        for (&(a, b), faces) in &edge_faces {
+            if faces.len() > 2 {
+                let mut ids: Vec<u32> = faces.iter().map(|(id, _)| *id).collect();
+                ids.sort();
+                report.non_manifold_edges.push((a, b, ids));
+            } else if faces.len() == 2 && faces[0].1 == faces[1].1 {
+                let (f0, f1) = (faces[0].0, faces[1].0);
+                report.inconsistent_winding.push((f0.min(f1), f0.max(f1)));
+            }
+        }
+        report.non_manifold_edges.sort();
+        report.inconsistent_winding.sort();
+
+        report.orphan_nodes = self
+            .node_map
+            .keys()
+            .filter(|id| !used_nodes.contains(id))
+            .copied()
+            .collect();
+        report.orphan_nodes.sort();
+
+        report.bow_tie_nodes = self.find_bow_tie_nodes(&edge_faces);
+
+        report
+    }
+
+    // 节点周围的面若通过经过该点的边不能全部连通，则为蝴蝶结点
+    fn find_bow_tie_nodes(&self, edge_faces: &HashMap<(u32, u32), Vec<(u32, bool)>>) -> Vec<u32> {
+        let mut node_edges: HashMap<u32, Vec<&Vec<(u32, bool)>>> = HashMap::new();
+        let mut node_faces: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for (&(a, b), faces) in edge_faces {
+            node_edges.entry(a).or_default().push(faces);
+            node_edges.entry(b).or_default().push(faces);
+            for &(face_id, _) in faces {
+                node_faces.entry(a).or_default().insert(face_id);
+                node_faces.entry(b).or_default().insert(face_id);
+            }
+        }
+
+        let mut result = Vec::new();
+        for (&node_id, faces) in &node_faces {
+            // 并查集合并共享边的面
+            let mut parent: HashMap<u32, u32> = faces.iter().map(|&id| (id, id)).collect();
+            fn find(parent: &mut HashMap<u32, u32>, id: u32) -> u32 {
+                let mut root = id;
+                while parent[&root] != root {
+                    root = parent[&root];
+                }
+                parent.insert(id, root);
+                root
+            }
+            for edge in &node_edges[&node_id] {
+                for i in 1..edge.len() {
+                    let r0 = find(&mut parent, edge[0].0);
+                    let r1 = find(&mut parent, edge[i].0);
+                    parent.insert(r0, r1);
+                }
+            }
+            let roots: HashSet<u32> = faces.iter().map(|&id| find(&mut parent, id)).collect();
+            if roots.len() > 1 {
+                result.push(node_id);
+            }
+        }
+        result.sort();
+        result
+    }
+}
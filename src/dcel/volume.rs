@@ -0,0 +1,338 @@
+use std::{collections::HashSet, fmt};
+
+use geo_types::MultiPolygon;
+
+use super::{
+    clip::ring_points,
+    geometry::{point_in_polygon, signed_area},
+    spatial::{QuadTree, Rect},
+    MeshCoverage,
+};
+
+// z = a * x + b * y + c
+type Plane = (f64, f64, f64);
+
+type Triangle = [(f64, f64, f64); 3];
+
+#[derive(Debug, Clone, Copy)]
+pub enum VolumeReference<'a> {
+    // 水平面，如设计高程或水位
+    Elevation(f64),
+    // 另一个网格的表面，如原始地形
+    Coverage(&'a MeshCoverage),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VolumeReport {
+    // 网格高于参考面的部分（挖方）的体积及平面面积
+    pub cut_volume: f64,
+    pub cut_area: f64,
+    // 网格低于参考面的部分（填方）的体积及平面面积；参考面为水位时即水体体积与湿面积
+    pub fill_volume: f64,
+    pub fill_area: f64,
+    // 参考网格未覆盖、因此未计入的平面面积
+    pub uncovered_area: f64,
+}
+
+impl VolumeReport {
+    pub fn get_net_volume(&self) -> f64 {
+        self.cut_volume - self.fill_volume
+    }
+
+    pub fn get_area(&self) -> f64 {
+        self.cut_area + self.fill_area
+    }
+}
+
+impl fmt::Display for VolumeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cut: {:.3} over {:.3}", self.cut_volume, self.cut_area)?;
+        writeln!(
+            f,
+            "  fill: {:.3} over {:.3}",
+            self.fill_volume, self.fill_area
+        )?;
+        write!(
+            f,
+            "  net: {:.3}, uncovered area: {:.3}",
+            self.get_net_volume(),
+            self.uncovered_area
+        )
+    }
+}
+
+// 参与计算的多边形环及其边的索引，环统一为逆时针
+struct Rings {
+    // 外环为 1，孔洞为 -1
+    signs: Vec<f64>,
+    points: Vec<Vec<(f64, f64)>>,
+    rects: Vec<Rect>,
+    // 边 id 为 edge_rings 的下标，对应所在环的序号
+    edge_index: QuadTree,
+    edge_rings: Vec<usize>,
+}
+
+impl Rings {
+    fn new(polygons: &MultiPolygon<f64>) -> Self {
+        let mut rings = Rings {
+            signs: Vec::new(),
+            points: Vec::new(),
+            rects: Vec::new(),
+            edge_index: QuadTree::new(),
+            edge_rings: Vec::new(),
+        };
+        for polygon in &polygons.0 {
+            let exterior = std::iter::once((1.0, polygon.exterior()));
+            let interiors = polygon.interiors().iter().map(|ring| (-1.0, ring));
+            for (sign, ring) in exterior.chain(interiors) {
+                let mut points = ring_points(ring);
+                let area = signed_area(&points);
+                let rect = match Rect::from_points(&points) {
+                    Some(rect) if points.len() >= 3 && area != 0.0 => rect,
+                    _ => continue,
+                };
+                if area < 0.0 {
+                    points.reverse();
+                }
+                let i = rings.points.len();
+                let len = points.len();
+                for j in 0..len {
+                    let edge = [points[j], points[(j + 1) % len]];
+                    if let Some(edge_rect) = Rect::from_points(&edge) {
+                        rings
+                            .edge_index
+                            .insert(rings.edge_rings.len() as u32, edge_rect);
+                        rings.edge_rings.push(i);
+                    }
+                }
+                rings.signs.push(sign);
+                rings.points.push(points);
+                rings.rects.push(rect);
+            }
+        }
+        rings
+    }
+
+    // 逆时针三角形（以 origin 为原点）与多边形的交，以 (外环为 1、孔洞为 -1, 逆时针多边形) 表示
+    fn intersect(&self, local: &[(f64, f64)], origin: (f64, f64)) -> Vec<(f64, Vec<(f64, f64)>)> {
+        let triangle: Vec<(f64, f64)> = local
+            .iter()
+            .map(|p| (p.0 + origin.0, p.1 + origin.1))
+            .collect();
+        let rect = match Rect::from_points(&triangle) {
+            Some(rect) => rect,
+            None => return Vec::new(),
+        };
+        let centroid = (
+            triangle.iter().map(|p| p.0).sum::<f64>() / 3.0,
+            triangle.iter().map(|p| p.1).sum::<f64>() / 3.0,
+        );
+        let crossed: HashSet<usize> = self
+            .edge_index
+            .query_rect(&rect)
+            .into_iter()
+            .map(|id| self.edge_rings[id as usize])
+            .collect();
+
+        let mut regions = Vec::new();
+        for (i, ring) in self.points.iter().enumerate() {
+            if !self.rects[i].intersects(&rect) {
+                continue;
+            }
+            if crossed.contains(&i) {
+                // 凸多边形裁剪任意多边形得到的结果可能含有重合的边，不影响面积与积分
+                let ring: Vec<(f64, f64)> = ring
+                    .iter()
+                    .map(|p| (p.0 - origin.0, p.1 - origin.1))
+                    .collect();
+                let clipped = clip_convex(&ring, local);
+                if clipped.len() >= 3 {
+                    regions.push((self.signs[i], clipped));
+                }
+            } else if point_in_polygon(centroid, ring) {
+                // 没有边经过三角形，三角形整个在环内
+                regions.push((self.signs[i], local.to_vec()));
+            }
+        }
+        regions
+    }
+}
+
+impl MeshCoverage {
+    // 网格表面与参考面之间的挖填方体积及面积，polygons 给出时只统计多边形内的部分；
+    // 四边形沿 n0-n2 对角线分成两个三角形，每个三角形与参考面的每个三角形求交后按线性插值精确积分
+    pub fn get_volume(
+        &self,
+        reference: VolumeReference<'_>,
+        polygons: Option<&MultiPolygon<f64>>,
+    ) -> VolumeReport {
+        let mut report = VolumeReport::default();
+        let rings = polygons.map(Rings::new);
+
+        let mut face_ids: Vec<&u32> = self.face_map.keys().collect();
+        face_ids.sort();
+        for face_id in face_ids {
+            for triangle in get_face_triangles(self, *face_id) {
+                // 以三角形第一个节点为原点，减小大坐标下的舍入误差
+                let origin = (triangle[0].0, triangle[0].1);
+                let plane = match get_plane(&triangle, origin) {
+                    Some(plane) => plane,
+                    None => continue,
+                };
+                let mut local: Vec<(f64, f64)> = triangle
+                    .iter()
+                    .map(|p| (p.0 - origin.0, p.1 - origin.1))
+                    .collect();
+                if signed_area(&local) < 0.0 {
+                    local.reverse();
+                }
+                let regions = match &rings {
+                    Some(rings) => rings.intersect(&local, origin),
+                    None => vec![(1.0, local)],
+                };
+
+                match reference {
+                    VolumeReference::Elevation(z) => {
+                        let d = (plane.0, plane.1, plane.2 - z);
+                        for (sign, region) in &regions {
+                            report.add_region(*sign, region, d);
+                        }
+                    }
+                    VolumeReference::Coverage(other) => {
+                        let mut region_area = 0.0;
+                        let mut covered_area = 0.0;
+                        for (sign, region) in &regions {
+                            region_area += sign * signed_area(region);
+                        }
+                        let points: Vec<(f64, f64)> = triangle.iter().map(|p| (p.0, p.1)).collect();
+                        let rect = Rect::from_points(&points).unwrap();
+                        for other_id in other.find_faces_in_rect(&rect) {
+                            for other_triangle in get_face_triangles(other, other_id) {
+                                let other_plane = match get_plane(&other_triangle, origin) {
+                                    Some(plane) => plane,
+                                    None => continue,
+                                };
+                                let other_points: Vec<(f64, f64)> = other_triangle
+                                    .iter()
+                                    .map(|p| (p.0 - origin.0, p.1 - origin.1))
+                                    .collect();
+                                let d = (
+                                    plane.0 - other_plane.0,
+                                    plane.1 - other_plane.1,
+                                    plane.2 - other_plane.2,
+                                );
+                                for (sign, region) in &regions {
+                                    let piece = clip_convex(region, &other_points);
+                                    if piece.len() >= 3 {
+                                        covered_area += sign * signed_area(&piece);
+                                        report.add_region(*sign, &piece, d);
+                                    }
+                                }
+                            }
+                        }
+                        report.uncovered_area += region_area - covered_area;
+                    }
+                }
+            }
+        }
+        report.uncovered_area = report.uncovered_area.max(0.0);
+        report
+    }
+}
+
+impl VolumeReport {
+    // 以 d 为网格与参考面的高差，按 d 的正负拆分区域后分别累加
+    fn add_region(&mut self, sign: f64, region: &[(f64, f64)], d: Plane) {
+        let above = clip_half_plane(region, |p| d.0 * p.0 + d.1 * p.1 + d.2);
+        let (area, integral) = integrate(&above, d);
+        self.cut_area += sign * area;
+        self.cut_volume += sign * integral;
+        let below = clip_half_plane(region, |p| -(d.0 * p.0 + d.1 * p.1 + d.2));
+        let (area, integral) = integrate(&below, d);
+        self.fill_area += sign * area;
+        self.fill_volume -= sign * integral;
+    }
+}
+
+// 面拆分成的三角形，引用了不存在节点的面跳过
+fn get_face_triangles(mesh: &MeshCoverage, face_id: u32) -> Vec<Triangle> {
+    let ids = mesh.face_map[&face_id].node_ids();
+    let nodes: Vec<(f64, f64, f64)> = ids
+        .iter()
+        .filter_map(|id| mesh.node_map.get(id).map(|n| (n.x, n.y, n.z)))
+        .collect();
+    if nodes.len() != ids.len() {
+        return Vec::new();
+    }
+    (1..nodes.len() - 1)
+        .map(|i| [nodes[0], nodes[i], nodes[i + 1]])
+        .collect()
+}
+
+// 过三角形三个节点、以 origin 为原点的平面，退化时为 None
+fn get_plane(triangle: &Triangle, origin: (f64, f64)) -> Option<Plane> {
+    let [p0, p1, p2] = triangle.map(|p| (p.0 - origin.0, p.1 - origin.1, p.2));
+    let (ux, uy, uz) = (p1.0 - p0.0, p1.1 - p0.1, p1.2 - p0.2);
+    let (vx, vy, vz) = (p2.0 - p0.0, p2.1 - p0.1, p2.2 - p0.2);
+    let det = ux * vy - uy * vx;
+    if det == 0.0 {
+        return None;
+    }
+    let a = (uz * vy - vz * uy) / det;
+    let b = (ux * vz - vx * uz) / det;
+    Some((a, b, p0.2 - a * p0.0 - b * p0.1))
+}
+
+// 保留 f >= 0 的部分，f 为线性函数
+fn clip_half_plane(points: &[(f64, f64)], f: impl Fn((f64, f64)) -> f64) -> Vec<(f64, f64)> {
+    let len = points.len();
+    let mut result = Vec::with_capacity(len + 2);
+    for i in 0..len {
+        let (p, q) = (points[i], points[(i + 1) % len]);
+        let (fp, fq) = (f(p), f(q));
+        if fp >= 0.0 {
+            result.push(p);
+        }
+        if (fp >= 0.0) != (fq >= 0.0) {
+            let t = fp / (fp - fq);
+            result.push((p.0 + (q.0 - p.0) * t, p.1 + (q.1 - p.1) * t));
+        }
+    }
+    result
+}
+
+// Sutherland-Hodgman：用凸多边形 convex 依次按每条边裁剪 points
+fn clip_convex(points: &[(f64, f64)], convex: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let sign = signed_area(convex).signum();
+    let len = convex.len();
+    let mut result = points.to_vec();
+    for i in 0..len {
+        if result.len() < 3 {
+            return Vec::new();
+        }
+        let (a, b) = (convex[i], convex[(i + 1) % len]);
+        result = clip_half_plane(&result, |p| {
+            sign * ((b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0))
+        });
+    }
+    result
+}
+
+// 多边形的有向面积及线性函数 d 在其上的有向积分
+fn integrate(points: &[(f64, f64)], d: Plane) -> (f64, f64) {
+    let len = points.len();
+    if len < 3 {
+        return (0.0, 0.0);
+    }
+    let (mut area, mut mx, mut my) = (0.0, 0.0, 0.0);
+    for i in 0..len {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % len];
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        mx += (x0 + x1) * cross;
+        my += (y0 + y1) * cross;
+    }
+    let (area, mx, my) = (area / 2.0, mx / 6.0, my / 6.0);
+    (area, d.0 * mx + d.1 * my + d.2 * area)
+}
@@ -1,6 +1,10 @@
-use wgpu::{util::DeviceExt, CommandEncoder, Device, StoreOp, SurfaceConfiguration, TextureView};
+use wgpu::{
+    util::DeviceExt, CommandEncoder, Device, PrimitiveTopology, StoreOp, SurfaceConfiguration,
+    TextureView,
+};
 
 use crate::{
+    dcel::quality::QualityMetric,
     render::{create_render_pipeline, uniform4f},
     state::State,
 };
@@ -50,8 +54,44 @@ impl Vertex {
     }
 }
 
+// 按面着色的顶点，每个面单独使用一组顶点
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ColorVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl ColorVertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayerType {
+    Mesh,
+    Boundary,
+    // 按质量指标填充面
+    Quality(QualityMetric),
+    // 按等高距生成的等高线，按高程着色
+    Contour(f64),
+    // 按 State 中当前的数据集及时间步填充
+    Dataset,
+}
+
 pub struct Layer {
     pub coverage_id: String,
+    pub layer_type: LayerType,
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub num_indices: u32,
@@ -65,6 +105,16 @@ impl Layer {
         state: &State,
         device: &Device,
         config: &SurfaceConfiguration,
+    ) -> Self {
+        Layer::new_with_color(coverage_id, [0.0, 0.0, 0.0, 1.0], state, device, config)
+    }
+
+    pub fn new_with_color(
+        coverage_id: String,
+        color_uniform: [f32; 4],
+        state: &State,
+        device: &Device,
+        config: &SurfaceConfiguration,
     ) -> Self {
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
@@ -83,8 +133,6 @@ impl Layer {
             source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
         });
 
-        let color_uniform: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
-
         let (color_bind_group_layout, color_bind_group) =
             uniform4f("color", color_uniform, device, wgpu::ShaderStages::FRAGMENT);
 
@@ -94,10 +142,12 @@ impl Layer {
             &[&color_bind_group_layout, &state.camera_bind_group_layout],
             &[Vertex::desc()],
             config.format,
+            PrimitiveTopology::LineList,
         );
 
         Self {
             coverage_id,
+            layer_type: LayerType::Mesh,
             vertex_buffer,
             index_buffer,
             num_indices,
@@ -106,7 +156,99 @@ impl Layer {
         }
     }
 
-    pub fn setdata(&mut self, vertices: Vec<Vertex>, indices: Vec<u32>, device: &Device) {
+    // 质量着色图层，使用顶点颜色填充三角形
+    pub fn new_quality(
+        coverage_id: String,
+        metric: QualityMetric,
+        state: &State,
+        device: &Device,
+        config: &SurfaceConfiguration,
+    ) -> Self {
+        Layer::new_color_vertex(
+            coverage_id,
+            LayerType::Quality(metric),
+            PrimitiveTopology::TriangleList,
+            state,
+            device,
+            config,
+        )
+    }
+
+    // 等高线图层，按线段绘制
+    pub fn new_contour(
+        coverage_id: String,
+        interval: f64,
+        state: &State,
+        device: &Device,
+        config: &SurfaceConfiguration,
+    ) -> Self {
+        Layer::new_color_vertex(
+            coverage_id,
+            LayerType::Contour(interval),
+            PrimitiveTopology::LineList,
+            state,
+            device,
+            config,
+        )
+    }
+
+    // 按当前数据集及时间步着色的图层
+    pub fn new_dataset(
+        coverage_id: String,
+        state: &State,
+        device: &Device,
+        config: &SurfaceConfiguration,
+    ) -> Self {
+        Layer::new_color_vertex(
+            coverage_id,
+            LayerType::Dataset,
+            PrimitiveTopology::TriangleList,
+            state,
+            device,
+            config,
+        )
+    }
+
+    // 使用 ColorVertex 顶点颜色的图层
+    fn new_color_vertex(
+        coverage_id: String,
+        layer_type: LayerType,
+        topology: PrimitiveTopology,
+        state: &State,
+        device: &Device,
+        config: &SurfaceConfiguration,
+    ) -> Self {
+        let mut layer = Layer::new(coverage_id, state, device, config);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Color Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("quality.wgsl").into()),
+        });
+        let (color_bind_group_layout, color_bind_group) = uniform4f(
+            "color",
+            [1.0, 1.0, 1.0, 1.0],
+            device,
+            wgpu::ShaderStages::FRAGMENT,
+        );
+        layer.render_pipeline = create_render_pipeline(
+            shader,
+            device,
+            &[&color_bind_group_layout, &state.camera_bind_group_layout],
+            &[ColorVertex::desc()],
+            config.format,
+            topology,
+        );
+        layer.color_bind_group = color_bind_group;
+        layer.layer_type = layer_type;
+        layer
+    }
+
+    pub fn setdata<T: bytemuck::Pod>(
+        &mut self,
+        vertices: Vec<T>,
+        indices: Vec<u32>,
+        device: &Device,
+    ) {
         self.vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
             contents: bytemuck::cast_slice(&vertices),
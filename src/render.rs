@@ -48,6 +48,7 @@ pub fn create_render_pipeline(
     bind_group_layouts: &[&BindGroupLayout],
     buffers: &[VertexBufferLayout],
     format: TextureFormat,
+    topology: PrimitiveTopology,
 ) -> RenderPipeline {
     let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Render Pipeline Layout"),
@@ -78,10 +79,14 @@ pub fn create_render_pipeline(
             compilation_options: PipelineCompilationOptions::default(),
         }),
         primitive: PrimitiveState {
-            topology: PrimitiveTopology::LineList,
+            topology,
             strip_index_format: None,
             front_face: FrontFace::Ccw,
-            cull_mode: Some(Face::Back),
+            // 网格的面可能是顺时针的，填充时不能剔除背面
+            cull_mode: match topology {
+                PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip => None,
+                _ => Some(Face::Back),
+            },
             // 如果将该字段设置为除了 Fill 之外的任何职值， 都
             // 需要 Features::NON_FILL_POLYGON_MODE
             polygon_mode: PolygonMode::Fill,
@@ -110,6 +110,14 @@ impl Scene {
         ];
     }
 
+    // 屏幕像素坐标转换为图层的局部坐标（相对网格中心），不考虑旋转
+    pub fn screen_to_local(&self, position: [f64; 2]) -> [f64; 2] {
+        [
+            (position[0] - self.w / 2.0) / self.zoom - self.tx,
+            (self.h / 2.0 - position[1]) / self.zoom - self.ty,
+        ]
+    }
+
     pub fn scale_on_mouse_wheel(&mut self, times: f64) {
         self.scale(times);
 
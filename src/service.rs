@@ -1,8 +1,20 @@
 use crate::{
-    dcel::MeshCoverage,
-    layer::{Layer, Vertex},
+    dcel::{
+        contour::Contour,
+        dataset::{Dataset, DatasetLocation, DatasetValues},
+        profile::Profile,
+        quality::QualityMetric,
+        transform::PointPair,
+        MeshCoverage,
+    },
+    layer::{ColorVertex, Layer, Vertex},
 };
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
 use serde::{Deserialize, Serialize};
+use shapefile::{
+    dbase::{FieldValue, Record, TableWriterBuilder},
+    PointZ, PolygonRing, PolylineZ, Shape,
+};
 use std::{collections::HashMap, fs, path::Path, path::PathBuf};
 use wgpu::Device;
 
@@ -48,13 +60,18 @@ fn generate_vec_u32_from_bytes(bytes: Vec<u8>) -> Vec<u32> {
     vec_u32
 }
 
+fn get_mesh_file_path(root_path: &Path, cov_id: &str, extension: &str) -> PathBuf {
+    let mut path_buf = PathBuf::new();
+    path_buf.push(root_path);
+    path_buf.push("Geometry");
+    path_buf.push("Mesh");
+    path_buf.push(cov_id);
+    path_buf.set_extension(extension);
+    path_buf
+}
+
 fn read_node_file(root_path: &Path, cov_id: String) -> Vec<f64> {
-    let mut node_path_buf = PathBuf::new();
-    node_path_buf.push(root_path);
-    node_path_buf.push("Geometry");
-    node_path_buf.push("Mesh");
-    node_path_buf.push(cov_id);
-    node_path_buf.set_extension("node");
+    let node_path_buf = get_mesh_file_path(root_path, &cov_id, "node");
     println!("{:#?}", node_path_buf);
     let node_buff = fs::read(node_path_buf).unwrap();
     let coordinate_buff: Vec<f64> = generate_vec_f64_from_bytes(node_buff);
@@ -62,12 +79,7 @@ fn read_node_file(root_path: &Path, cov_id: String) -> Vec<f64> {
 }
 
 fn read_face_file(root_path: &Path, cov_id: String) -> Vec<u32> {
-    let mut face_path_buf = PathBuf::new();
-    face_path_buf.push(root_path);
-    face_path_buf.push("Geometry");
-    face_path_buf.push("Mesh");
-    face_path_buf.push(cov_id);
-    face_path_buf.set_extension("face");
+    let face_path_buf = get_mesh_file_path(root_path, &cov_id, "face");
     let face_buff = fs::read(face_path_buf).unwrap();
     let index_buff: Vec<u32> = generate_vec_u32_from_bytes(face_buff);
     index_buff
@@ -82,7 +94,9 @@ impl Service {
         let node_buff = read_node_file(root_path, id.clone());
         let len_node = node_buff.len() / 3;
         for n in 0..len_node {
-            coverage.create_node(
+            // .face 文件中的节点编号从 1 开始
+            coverage.create_node_with_id(
+                n as u32 + 1,
                 node_buff[n * 3 + 0],
                 node_buff[n * 3 + 1],
                 node_buff[n * 3 + 2],
@@ -100,9 +114,76 @@ impl Service {
                 face_buff[n * 4 + 3],
             );
         }
+        // 构建半边拓扑
+        println!("generate half edges...");
+        coverage.generate_half_edges();
         println!("mesh coverage loaded.");
     }
 
+    // 按节点 id 从小到大写出 .node 与 .face 文件，面引用的节点编号为写出顺序（从 1 开始）
+    pub fn save_mesh(root_path: &Path, coverage: &MeshCoverage) -> std::io::Result<()> {
+        let mut node_ids: Vec<u32> = coverage.node_map.keys().copied().collect();
+        node_ids.sort();
+        let mut index: HashMap<u32, u32> = HashMap::new();
+        let mut node_buff: Vec<u8> = Vec::with_capacity(node_ids.len() * 24);
+        for (i, id) in node_ids.iter().enumerate() {
+            let node = coverage.node_map[id];
+            for value in [node.x, node.y, node.z] {
+                node_buff.extend_from_slice(&value.to_le_bytes());
+            }
+            index.insert(*id, i as u32 + 1);
+        }
+
+        let mut face_ids: Vec<u32> = coverage.face_map.keys().copied().collect();
+        face_ids.sort();
+        let mut face_buff: Vec<u8> = Vec::with_capacity(face_ids.len() * 16);
+        for id in face_ids {
+            let face = coverage.face_map[&id];
+            for node_id in [face.n0, face.n1, face.n2, face.n3] {
+                let i = index.get(&node_id).copied().unwrap_or(0);
+                face_buff.extend_from_slice(&i.to_le_bytes());
+            }
+        }
+
+        let node_path_buf = get_mesh_file_path(root_path, &coverage.id, "node");
+        if let Some(dir) = node_path_buf.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(node_path_buf, node_buff)?;
+        fs::write(
+            get_mesh_file_path(root_path, &coverage.id, "face"),
+            face_buff,
+        )
+    }
+
+    // 写出包含半边拓扑及数据集的二进制快照 <id>.snap，见 MeshCoverage::to_snapshot
+    pub fn save_snapshot(root_path: &Path, coverage: &MeshCoverage) -> std::io::Result<PathBuf> {
+        let path_buf = get_mesh_file_path(root_path, &coverage.id, "snap");
+        if let Some(dir) = path_buf.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path_buf, coverage.to_snapshot())?;
+        Ok(path_buf)
+    }
+
+    pub fn load_snapshot(path_buf: &PathBuf) -> std::io::Result<MeshCoverage> {
+        MeshCoverage::from_snapshot(&fs::read(path_buf)?)
+    }
+
+    pub fn save_profile(
+        root_path: &Path,
+        cov_id: &str,
+        profile: &Profile,
+    ) -> std::io::Result<PathBuf> {
+        let path_buf = get_mesh_file_path(root_path, &format!("{}_profile", cov_id), "csv");
+        if let Some(dir) = path_buf.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut writer = std::io::BufWriter::new(fs::File::create(&path_buf)?);
+        profile.write_csv(&mut writer)?;
+        Ok(path_buf)
+    }
+
     pub fn set_test_data(device: &Device, layer: &mut Layer) {
         let vertices: Vec<Vertex> = [
             Vertex {
@@ -128,7 +209,8 @@ impl Service {
         layer.setdata(vertices, indices, device);
     }
 
-    pub fn set_mesh_data(device: &Device, coverage: &mut MeshCoverage, layer: &mut Layer) {
+    // 网格中心，所有图层的顶点都相对该点偏移
+    pub fn get_mesh_center(coverage: &mut MeshCoverage) -> (f64, f64) {
         let bbox3 = coverage.get_bbox3();
         println!("bbox3:{:#?}", bbox3,);
         let c_x = (bbox3.min_x + bbox3.max_x) / 2.0;
@@ -137,6 +219,11 @@ impl Service {
         let rang_y = bbox3.max_y - bbox3.min_y;
         let rang_z = bbox3.max_z - bbox3.min_z;
         println!("c_x:{c_x},c_y:{c_y},rang_x:{rang_x},rang_y:{rang_y},rang_z:{rang_z}");
+        (c_x, c_y)
+    }
+
+    pub fn set_mesh_data(device: &Device, coverage: &mut MeshCoverage, layer: &mut Layer) {
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
 
         let mut vertices: Vec<Vertex> = Vec::with_capacity(coverage.node_map.len());
         let mut map: HashMap<u32, u32> = HashMap::new();
@@ -153,13 +240,294 @@ impl Service {
         // let ids: Vec<u32> = state.coverage.generate_face_buffer();
         let len_ids = ids.len();
         let mut indices: Vec<u32> = Vec::with_capacity(len_ids);
-        for i in 0..len_ids {
-            indices.push(*map.get(&ids[i]).unwrap());
+        for pair in ids.chunks(2) {
+            // 跳过引用了不存在节点的边，见 MeshCoverage::validate
+            if let (Some(&i0), Some(&i1)) = (map.get(&pair[0]), map.get(&pair[1])) {
+                indices.push(i0);
+                indices.push(i1);
+            }
         }
 
         layer.setdata(vertices, indices, device);
     }
 
+    pub fn set_boundary_data(device: &Device, coverage: &mut MeshCoverage, layer: &mut Layer) {
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
+        let boundary = coverage.get_boundary();
+
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        for nodes in boundary.loops() {
+            // 不存在的节点处断开，只连接相邻的两个存在的节点
+            let loop_indices: Vec<Option<u32>> = nodes
+                .iter()
+                .map(|id| {
+                    let node = coverage.node_map.get(id)?;
+                    vertices.push(Vertex {
+                        position: [(node.x - c_x) as f32, (node.y - c_y) as f32, node.z as f32],
+                        id: *id,
+                    });
+                    Some(vertices.len() as u32 - 1)
+                })
+                .collect();
+            let len = loop_indices.len();
+            for i in 0..len {
+                if let (Some(i0), Some(i1)) = (loop_indices[i], loop_indices[(i + 1) % len]) {
+                    indices.push(i0);
+                    indices.push(i1);
+                }
+            }
+        }
+
+        layer.setdata(vertices, indices, device);
+    }
+
+    // 按质量指标填充面，色带从蓝（好）到红（差），范围取所有面的最小值与最大值
+    pub fn set_quality_data(
+        device: &Device,
+        coverage: &mut MeshCoverage,
+        layer: &mut Layer,
+        metric: QualityMetric,
+    ) {
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
+        let quality_map = coverage.get_quality_map();
+
+        let (mut min, mut max) = (f64::MAX, f64::MIN);
+        for quality in quality_map.values() {
+            let value = quality.get(metric);
+            if value.is_finite() {
+                min = min.min(value);
+                max = max.max(value);
+            }
+        }
+        println!("{:?}: {} ~ {}", metric, min, max);
+
+        let mut face_ids: Vec<&u32> = quality_map.keys().collect();
+        face_ids.sort();
+        let mut vertices: Vec<ColorVertex> = Vec::with_capacity(face_ids.len() * 4);
+        let mut indices: Vec<u32> = Vec::with_capacity(face_ids.len() * 6);
+        for face_id in face_ids {
+            let value = quality_map[face_id].get(metric);
+            let mut t = if max > min {
+                ((value - min) / (max - min)).clamp(0.0, 1.0)
+            } else if value.is_finite() {
+                0.0
+            } else {
+                1.0
+            };
+            if metric.higher_is_better() {
+                t = 1.0 - t;
+            }
+            let color = Service::get_ramp_color(t);
+
+            let first = vertices.len() as u32;
+            let ids = coverage.face_map[face_id].node_ids();
+            for id in &ids {
+                let node = coverage.node_map[id];
+                vertices.push(ColorVertex {
+                    position: [(node.x - c_x) as f32, (node.y - c_y) as f32, node.z as f32],
+                    color,
+                });
+            }
+            for i in 1..ids.len() as u32 - 1 {
+                indices.push(first);
+                indices.push(first + i);
+                indices.push(first + i + 1);
+            }
+        }
+
+        layer.setdata(vertices, indices, device);
+    }
+
+    // 按数据集在 step 时间步的值着色，色带范围取所有时间步的最小值与最大值以便比较；
+    // 节点数据集的颜色在面内插值，缺少值的面不绘制
+    pub fn set_dataset_data(
+        device: &Device,
+        coverage: &mut MeshCoverage,
+        layer: &mut Layer,
+        name: Option<&str>,
+        step: usize,
+    ) {
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
+        let mut vertices: Vec<ColorVertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        let dataset = name.and_then(|name| coverage.get_dataset(name));
+        let statistics = dataset.and_then(|dataset| dataset.get_statistics());
+        if let (Some(dataset), Some(statistics)) = (dataset, statistics) {
+            let step = step.min(dataset.steps.len().saturating_sub(1));
+            let color = |value: f64| {
+                let t = if statistics.max > statistics.min {
+                    ((value - statistics.min) / (statistics.max - statistics.min)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                Service::get_ramp_color(t)
+            };
+
+            let mut face_ids: Vec<&u32> = coverage.face_map.keys().collect();
+            face_ids.sort();
+            for face_id in face_ids {
+                let ids = coverage.face_map[face_id].node_ids();
+                let colors: Option<Vec<[f32; 4]>> = match dataset.location {
+                    DatasetLocation::Node => ids
+                        .iter()
+                        .map(|id| dataset.get_value(step, *id).map(color))
+                        .collect(),
+                    DatasetLocation::Face => dataset
+                        .get_value(step, *face_id)
+                        .map(|value| vec![color(value); ids.len()]),
+                };
+                let colors = match colors {
+                    Some(colors) if ids.iter().all(|id| coverage.node_map.contains_key(id)) => {
+                        colors
+                    }
+                    _ => continue,
+                };
+
+                let first = vertices.len() as u32;
+                for (id, color) in ids.iter().zip(colors) {
+                    let node = coverage.node_map[id];
+                    vertices.push(ColorVertex {
+                        position: [(node.x - c_x) as f32, (node.y - c_y) as f32, node.z as f32],
+                        color,
+                    });
+                }
+                for i in 1..ids.len() as u32 - 1 {
+                    indices.push(first);
+                    indices.push(first + i);
+                    indices.push(first + i + 1);
+                }
+            }
+        }
+
+        layer.setdata(vertices, indices, device);
+    }
+
+    // 约分成 10 段的等高距，取 1、2、5 乘以 10 的整数次幂
+    pub fn get_contour_interval(coverage: &mut MeshCoverage) -> f64 {
+        let bbox3 = coverage.get_bbox3();
+        let step = (bbox3.max_z - bbox3.min_z) / 10.0;
+        if !(step > 0.0 && step.is_finite()) {
+            return 1.0;
+        }
+        let base = 10f64.powf(step.log10().floor());
+        let ratio = step / base;
+        if ratio < 1.5 {
+            base
+        } else if ratio < 3.5 {
+            base * 2.0
+        } else if ratio < 7.5 {
+            base * 5.0
+        } else {
+            base * 10.0
+        }
+    }
+
+    // 等高线绘制在其高程上，颜色按等高线值在所有等高线中的位置取色带
+    pub fn set_contour_data(
+        device: &Device,
+        coverage: &mut MeshCoverage,
+        layer: &mut Layer,
+        interval: f64,
+    ) {
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
+        let contours = coverage.get_contours(interval);
+
+        let (mut min, mut max) = (f64::MAX, f64::MIN);
+        for contour in &contours {
+            min = min.min(contour.level);
+            max = max.max(contour.level);
+        }
+        println!(
+            "{}: {} contours, {} ~ {} at {}",
+            coverage.id,
+            contours.len(),
+            min,
+            max,
+            interval
+        );
+
+        let mut vertices: Vec<ColorVertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        for contour in &contours {
+            let t = if max > min {
+                (contour.level - min) / (max - min)
+            } else {
+                0.0
+            };
+            let color = Service::get_ramp_color(t);
+            let first = vertices.len() as u32;
+            for &(x, y) in &contour.points {
+                vertices.push(ColorVertex {
+                    position: [(x - c_x) as f32, (y - c_y) as f32, contour.level as f32],
+                    color,
+                });
+            }
+            let len = contour.points.len() as u32;
+            let segments = if contour.closed { len } else { len - 1 };
+            for i in 0..segments {
+                indices.push(first + i);
+                indices.push(first + (i + 1) % len);
+            }
+        }
+
+        layer.setdata(vertices, indices, device);
+    }
+
+    // 写出与网格文件同目录的 <id>_contour.shp，PolylineZ 的 z 为等高线值，属性表 LEVEL 字段同样记录等高线值；
+    // 闭合等高线首尾点重复
+    pub fn save_contours(
+        root_path: &Path,
+        cov_id: &str,
+        contours: &[Contour],
+    ) -> Result<(), shapefile::Error> {
+        let path = get_mesh_file_path(root_path, &format!("{}_contour", cov_id), "shp");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let table_builder =
+            TableWriterBuilder::new().add_numeric_field("LEVEL".try_into().unwrap(), 18, 6);
+        let mut writer = shapefile::Writer::from_path(&path, table_builder)?;
+        for contour in contours {
+            let mut points: Vec<PointZ> = contour
+                .points
+                .iter()
+                .map(|&(x, y)| PointZ::new(x, y, contour.level, shapefile::NO_DATA))
+                .collect();
+            if contour.closed {
+                points.push(points[0]);
+            }
+            let mut record = Record::default();
+            record.insert(
+                "LEVEL".to_string(),
+                FieldValue::Numeric(Some(contour.level)),
+            );
+            writer.write_shape_and_record(&PolylineZ::new(points), &record)?;
+        }
+        Ok(())
+    }
+
+    // 蓝、青、绿、黄、红五段色带，t 取 0~1
+    fn get_ramp_color(t: f64) -> [f32; 4] {
+        let stops: [[f32; 3]; 5] = [
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+        ];
+        let x = t as f32 * 4.0;
+        let i = (x.floor() as usize).min(3);
+        let f = x - i as f32;
+        let (a, b) = (stops[i], stops[i + 1]);
+        [
+            a[0] + (b[0] - a[0]) * f,
+            a[1] + (b[1] - a[1]) * f,
+            a[2] + (b[2] - a[2]) * f,
+            1.0,
+        ]
+    }
+
     pub fn read_grmsp_coverage_file(path_buf: &PathBuf) -> Vec<CoverageJSON> {
         let name = path_buf.file_name().unwrap();
         let stem = path_buf.file_stem().unwrap();
@@ -195,4 +563,188 @@ impl Service {
             Err(_) => [].to_vec(),
         }
     }
+
+    // 读取 shapefile 中的面，外环开始一个新多边形，内环作为上一个多边形的孔洞
+    pub fn read_shp_polygons(path_buf: &PathBuf) -> MultiPolygon<f64> {
+        let mut polygons: Vec<Polygon<f64>> = Vec::new();
+        let shapes = match shapefile::read_shapes(path_buf) {
+            Ok(shapes) => shapes,
+            Err(e) => {
+                eprintln!("{:?}", e);
+                return MultiPolygon(polygons);
+            }
+        };
+        for shape in shapes {
+            let rings: Vec<(bool, Vec<(f64, f64)>)> = match shape {
+                Shape::Polygon(polygon) => polygon
+                    .into_inner()
+                    .into_iter()
+                    .map(|ring| match ring {
+                        PolygonRing::Outer(points) => {
+                            (true, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                        PolygonRing::Inner(points) => {
+                            (false, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                    })
+                    .collect(),
+                Shape::PolygonM(polygon) => polygon
+                    .into_inner()
+                    .into_iter()
+                    .map(|ring| match ring {
+                        PolygonRing::Outer(points) => {
+                            (true, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                        PolygonRing::Inner(points) => {
+                            (false, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                    })
+                    .collect(),
+                Shape::PolygonZ(polygon) => polygon
+                    .into_inner()
+                    .into_iter()
+                    .map(|ring| match ring {
+                        PolygonRing::Outer(points) => {
+                            (true, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                        PolygonRing::Inner(points) => {
+                            (false, points.iter().map(|p| (p.x, p.y)).collect())
+                        }
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for (outer, points) in rings {
+                let ring = LineString(
+                    points
+                        .into_iter()
+                        .map(|(x, y)| Coordinate { x, y })
+                        .collect(),
+                );
+                if outer {
+                    polygons.push(Polygon::new(ring, vec![]));
+                } else if let Some(polygon) = polygons.last_mut() {
+                    polygon.interiors_push(ring);
+                }
+            }
+        }
+        MultiPolygon(polygons)
+    }
+
+    // 读取 ASCII .dat 格式的节点数据集，一个文件可含多个数据集；
+    // 值按节点在文件中的顺序以 1..=ND 为 id，与 load_mesh 的编号一致，单元状态标志忽略
+    pub fn read_dat_file(path_buf: &PathBuf) -> Vec<Dataset> {
+        let mut datasets = Vec::new();
+        let s = match fs::read_to_string(path_buf) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("{:?}", e);
+                return datasets;
+            }
+        };
+        let default_name = path_buf.file_stem().unwrap().to_string_lossy().to_string();
+
+        let mut lines = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty());
+        let (mut node_count, mut cell_count) = (0, 0);
+        let mut vector = false;
+        let mut dataset = Dataset::new(default_name.clone(), DatasetLocation::Node);
+        while let Some(line) = lines.next() {
+            let (card, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            match card.to_uppercase().as_str() {
+                "BEGSCL" => vector = false,
+                "BEGVEC" => vector = true,
+                "ND" => node_count = rest.trim().parse().unwrap_or(0),
+                "NC" => cell_count = rest.trim().parse().unwrap_or(0),
+                "NAME" => dataset.name = rest.trim().trim_matches('"').to_string(),
+                "TS" => {
+                    let values: Vec<f64> = rest
+                        .split_whitespace()
+                        .filter_map(|v| v.parse().ok())
+                        .collect();
+                    let (status, time) = match values[..] {
+                        [status, time, ..] => (status, time),
+                        _ => continue,
+                    };
+                    if status != 0.0 {
+                        lines.by_ref().take(cell_count).for_each(drop);
+                    }
+                    let rows: Vec<Vec<f64>> = lines
+                        .by_ref()
+                        .take(node_count)
+                        .map(|line| {
+                            line.split_whitespace()
+                                .filter_map(|v| v.parse().ok())
+                                .collect()
+                        })
+                        .collect();
+                    let values = if vector {
+                        DatasetValues::Vector(
+                            rows.iter()
+                                .enumerate()
+                                .filter(|(_, row)| row.len() >= 2)
+                                .map(|(i, row)| (i as u32 + 1, (row[0], row[1])))
+                                .collect(),
+                        )
+                    } else {
+                        DatasetValues::Scalar(
+                            rows.iter()
+                                .enumerate()
+                                .filter(|(_, row)| !row.is_empty())
+                                .map(|(i, row)| (i as u32 + 1, row[0]))
+                                .collect(),
+                        )
+                    };
+                    dataset.add_step(time, values);
+                }
+                "ENDDS" => {
+                    let next = Dataset::new(default_name.clone(), DatasetLocation::Node);
+                    datasets.push(std::mem::replace(&mut dataset, next));
+                }
+                _ => {}
+            }
+        }
+        if !dataset.steps.is_empty() {
+            datasets.push(dataset);
+        }
+        datasets
+    }
+
+    // 读取散点文件，每行 x y z，可用空格、制表符或逗号分隔，无法解析的行跳过
+    pub fn read_xyz_file(path_buf: &PathBuf) -> Vec<[f64; 3]> {
+        let mut points = Vec::new();
+        if let Ok(s) = fs::read_to_string(path_buf) {
+            for line in s.lines() {
+                let values: Vec<f64> = line
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|v| !v.is_empty())
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+                if values.len() >= 3 {
+                    points.push([values[0], values[1], values[2]]);
+                }
+            }
+        }
+        points
+    }
+
+    // 控制点文件，每行为 原 x、原 y、目标 x、目标 y，以空白或逗号分隔
+    pub fn read_gcp_file(path_buf: &PathBuf) -> Vec<PointPair> {
+        let mut pairs = Vec::new();
+        if let Ok(s) = fs::read_to_string(path_buf) {
+            for line in s.lines() {
+                let values: Vec<f64> = line
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|v| !v.is_empty())
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+                if values.len() >= 4 {
+                    pairs.push(((values[0], values[1]), (values[2], values[3])));
+                }
+            }
+        }
+        pairs
+    }
 }
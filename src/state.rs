@@ -1,4 +1,9 @@
-use crate::{dcel::MeshCoverage, layer::Layer, scene::Scene};
+use crate::{
+    dcel::{clip::ClipOptions, MeshCoverage},
+    layer::{Layer, LayerType},
+    scene::Scene,
+    service::Service,
+};
 use std::{collections::HashMap, iter};
 
 use wgpu::{util::DeviceExt, Adapter, Device, Queue, StoreOp, Surface, SurfaceConfiguration};
@@ -10,6 +15,15 @@ pub struct State {
     pub camera_buffer: wgpu::Buffer,
     pub camera_bind_group_layout: wgpu::BindGroupLayout,
     pub camera_bind_group: wgpu::BindGroup,
+    // 数据集图层显示的数据集名称及时间步序号
+    pub active_dataset: Option<String>,
+    pub active_step: usize,
+    // 剖面线的顶点，局部坐标
+    pub section_line: Vec<[f64; 2]>,
+    // 简化时保留的断裂线，局部坐标；最后一条非空时为正在绘制的断裂线
+    pub breaklines: Vec<Vec<[f64; 2]>>,
+    // 拖入 .shp 时的裁剪方式
+    pub clip_options: ClipOptions,
 }
 
 impl State {
@@ -61,6 +75,11 @@ impl State {
             camera_buffer,
             camera_bind_group_layout,
             camera_bind_group,
+            active_dataset: None,
+            active_step: 0,
+            section_line: Vec::new(),
+            breaklines: Vec::new(),
+            clip_options: ClipOptions::default(),
         }
     }
 
@@ -153,4 +172,29 @@ impl State {
     pub fn add_layer(&mut self, layer: Layer) {
         self.layers.push(layer);
     }
+
+    // 网格修改后重新生成对应图层的缓冲区
+    pub fn refresh_layers(&mut self, device: &Device) {
+        for layer in &mut self.layers {
+            if let Some(coverage) = self.coverages.get_mut(&layer.coverage_id) {
+                match layer.layer_type {
+                    LayerType::Mesh => Service::set_mesh_data(device, coverage, layer),
+                    LayerType::Boundary => Service::set_boundary_data(device, coverage, layer),
+                    LayerType::Quality(metric) => {
+                        Service::set_quality_data(device, coverage, layer, metric)
+                    }
+                    LayerType::Contour(interval) => {
+                        Service::set_contour_data(device, coverage, layer, interval)
+                    }
+                    LayerType::Dataset => Service::set_dataset_data(
+                        device,
+                        coverage,
+                        layer,
+                        self.active_dataset.as_deref(),
+                        self.active_step,
+                    ),
+                }
+            }
+        }
+    }
 }
@@ -1,5 +1,9 @@
 use core::f64;
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use winit::{
     dpi::{PhysicalPosition, PhysicalSize},
@@ -7,7 +11,30 @@ use winit::{
     window::{Window, WindowId},
 };
 
-use crate::{dcel::MeshCoverage, layer::Layer, service::Service, state::State, wgpu_ctx::WgpuCtx};
+use crate::{
+    dcel::{
+        clip::{ClipCrossing, ClipKeep, ClipOptions},
+        dataset::DatasetLocation,
+        decimate::DecimateOptions,
+        delaunay::TriangulateOptions,
+        quality::QualityMetric,
+        smooth::{SmoothMethod, SmoothOptions},
+        transform::Affine,
+        volume::VolumeReference,
+        MeshCoverage,
+    },
+    layer::{Layer, LayerType},
+    service::Service,
+    state::State,
+    wgpu_ctx::WgpuCtx,
+};
+
+// 面数不超过该值的不连通小块视为孤岛
+const MAX_ISLAND_FACES: usize = 10;
+// 简化时允许的竖向偏差占高程范围的比例
+const DECIMATE_ERROR_RATIO: f64 = 0.01;
+// 清理时合并节点的容差占包围盒对角线的比例，面积阈值取容差的平方
+const CLEANUP_TOLERANCE_RATIO: f64 = 1e-6;
 
 struct Element {}
 
@@ -103,6 +130,548 @@ impl<'window> WinCtx<'window> {
         self.state.scene.translate(tx.clone(), ty.clone());
         self.redraw();
     }
+    pub fn split_quads(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let faces = coverage.split_all_quads();
+            println!("{}: {} triangles created.", coverage.id, faces.len());
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    pub fn merge_triangles(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let faces = coverage.merge_all_triangles(0.5);
+            println!("{}: {} quads created.", coverage.id, faces.len());
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 输出鼠标位置所在的面及插值高程
+    pub fn probe(&mut self) {
+        let local = self
+            .state
+            .scene
+            .screen_to_local(self.state.scene.mouse_position());
+        for coverage in self.state.coverages.values_mut() {
+            let (c_x, c_y) = Service::get_mesh_center(coverage);
+            let (x, y) = (local[0] + c_x, local[1] + c_y);
+            if let Some(node_id) = coverage.find_nearest_node(x, y) {
+                let node = coverage.node_map[&node_id];
+                println!(
+                    "{}: nearest node {} ({:.3}, {:.3}, {:.3})",
+                    coverage.id, node_id, node.x, node.y, node.z
+                );
+            }
+            match coverage.locate_point(x, y) {
+                Some(location) => {
+                    let z = location.interpolate(|id| coverage.node_map.get(&id).map(|n| n.z));
+                    println!(
+                        "{}: ({:.3}, {:.3}) face {}, z {:?}",
+                        coverage.id, x, y, location.face_id, z
+                    );
+                    let name = self.state.active_dataset.as_deref();
+                    if let Some(dataset) = name.and_then(|name| coverage.get_dataset(name)) {
+                        let step = self.state.active_step;
+                        let value = match dataset.location {
+                            DatasetLocation::Node => {
+                                location.interpolate(|id| dataset.get_value(step, id))
+                            }
+                            DatasetLocation::Face => dataset.get_value(step, location.face_id),
+                        };
+                        println!("{}: {} {:?}", coverage.id, dataset.name, value);
+                    }
+                }
+                None => println!("{}: ({:.3}, {:.3}) outside mesh", coverage.id, x, y),
+            }
+        }
+    }
+    // 有多个网格时计算 id 最小的网格相对其余各网格的挖填方，只有一个网格时以鼠标位置的插值高程为参考面
+    pub fn compute_volume(&mut self) {
+        let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+        coverage_ids.sort();
+        let coverage = match coverage_ids.first() {
+            Some(id) => self.state.coverages.get_mut(id).unwrap(),
+            None => return,
+        };
+        let (c_x, c_y) = Service::get_mesh_center(coverage);
+        let coverage = &self.state.coverages[&coverage_ids[0]];
+        if coverage_ids.len() > 1 {
+            for other_id in &coverage_ids[1..] {
+                let other = &self.state.coverages[other_id];
+                let report = coverage.get_volume(VolumeReference::Coverage(other), None);
+                println!("{} - {}: {}", coverage.id, other.id, report);
+            }
+            return;
+        }
+        let local = self
+            .state
+            .scene
+            .screen_to_local(self.state.scene.mouse_position());
+        let (x, y) = (local[0] + c_x, local[1] + c_y);
+        match coverage.interpolate_z_at(x, y) {
+            Some(z) => {
+                let report = coverage.get_volume(VolumeReference::Elevation(z), None);
+                println!("{} - elevation {:.3}: {}", coverage.id, z, report);
+            }
+            None => println!("{}: ({:.3}, {:.3}) outside mesh", coverage.id, x, y),
+        }
+    }
+    pub fn refine(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let faces = coverage.refine_all();
+            println!("{}: {} faces created.", coverage.id, faces.len());
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 将所有网格合并到 id 最小的网格中，容差为包围盒对角线的百万分之一
+    pub fn merge_coverages(&mut self) {
+        let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+        coverage_ids.sort();
+        if coverage_ids.len() < 2 {
+            return;
+        }
+        let mut target = self.state.coverages.remove(&coverage_ids[0]).unwrap();
+        for coverage_id in &coverage_ids[1..] {
+            let other = self.state.coverages.remove(coverage_id).unwrap();
+            let mut bbox3 = target.get_bbox3();
+            for &node in other.node_map.values() {
+                bbox3.eat(node);
+            }
+            let tolerance =
+                ((bbox3.max_x - bbox3.min_x).powi(2) + (bbox3.max_y - bbox3.min_y).powi(2)).sqrt()
+                    * 1e-6;
+            let report = target.merge(&other, tolerance);
+            println!("{} + {}: {}", target.id, other.id, report);
+        }
+        self.state
+            .layers
+            .retain(|layer| !coverage_ids[1..].contains(&layer.coverage_id));
+        self.state.add_coverage(target);
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 按带宽优化的顺序重新编号后导出到 export 目录，显示等高线时一并导出
+    pub fn export(&mut self) {
+        let root_path = Path::new("export");
+        let intervals: HashMap<String, f64> = self
+            .state
+            .layers
+            .iter()
+            .filter_map(|layer| match layer.layer_type {
+                LayerType::Contour(interval) => Some((layer.coverage_id.clone(), interval)),
+                _ => None,
+            })
+            .collect();
+        for coverage in self.state.coverages.values_mut() {
+            let renumbering = coverage.renumber(true);
+            println!(
+                "{}: bandwidth {} -> {}",
+                coverage.id, renumbering.bandwidth_before, renumbering.bandwidth_after
+            );
+            if !renumbering.dropped_faces.is_empty() {
+                println!(
+                    "{}: dropped faces with missing nodes {:?}",
+                    coverage.id, renumbering.dropped_faces
+                );
+            }
+            match Service::save_mesh(root_path, coverage) {
+                Ok(_) => println!("{}: exported to {:?}", coverage.id, root_path),
+                Err(e) => eprintln!("{}: export failed, {:?}", coverage.id, e),
+            }
+            if let Some(&interval) = intervals.get(&coverage.id) {
+                let contours = coverage.get_contours(interval);
+                match Service::save_contours(root_path, &coverage.id, &contours) {
+                    Ok(_) => println!("{}: {} contours exported", coverage.id, contours.len()),
+                    Err(e) => eprintln!("{}: contour export failed, {:?}", coverage.id, e),
+                }
+            }
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 保存所有网格的快照到 export 目录，可拖入窗口重新打开
+    pub fn save_snapshots(&mut self) {
+        let root_path = Path::new("export");
+        for coverage in self.state.coverages.values() {
+            match Service::save_snapshot(root_path, coverage) {
+                Ok(path_buf) => println!("{}: saved to {:?}", coverage.id, path_buf),
+                Err(e) => eprintln!("{}: snapshot failed, {:?}", coverage.id, e),
+            }
+        }
+    }
+    // 光顺所有网格，输出前后的最小内角以便比较
+    pub fn smooth(&mut self, method: SmoothMethod) {
+        let options = SmoothOptions {
+            method,
+            ..Default::default()
+        };
+        for coverage in self.state.coverages.values_mut() {
+            let min_angle = |coverage: &MeshCoverage| {
+                coverage
+                    .get_quality_map()
+                    .values()
+                    .fold(f64::MAX, |a, q| a.min(q.min_angle))
+            };
+            let before = min_angle(coverage);
+            let moved = coverage.smooth(&options);
+            println!(
+                "{}: {:?} smoothing moved {} nodes, min angle {:.2} -> {:.2}",
+                coverage.id,
+                method,
+                moved,
+                before,
+                min_angle(coverage)
+            );
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 依次切换质量着色的指标，最后一个指标之后关闭着色
+    pub fn cycle_quality_metric(&mut self) {
+        let current = self
+            .state
+            .layers
+            .iter()
+            .find_map(|layer| match layer.layer_type {
+                LayerType::Quality(metric) => Some(metric),
+                _ => None,
+            });
+        self.state
+            .layers
+            .retain(|layer| !matches!(layer.layer_type, LayerType::Quality(_)));
+
+        let next = match current {
+            Some(metric) => {
+                let i = QualityMetric::ALL
+                    .iter()
+                    .position(|m| *m == metric)
+                    .unwrap();
+                QualityMetric::ALL.get(i + 1).copied()
+            }
+            None => Some(QualityMetric::ALL[0]),
+        };
+        if let Some(metric) = next {
+            let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+            coverage_ids.sort();
+            // 填充图层放在最下面，网格线绘制在其上
+            for (i, coverage_id) in coverage_ids.into_iter().enumerate() {
+                let layer = Layer::new_quality(
+                    coverage_id,
+                    metric,
+                    &self.state,
+                    &self.wgpu_ctx.device,
+                    &self.wgpu_ctx.surface_config,
+                );
+                self.state.layers.insert(i, layer);
+            }
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 依次切换着色的数据集（所有网格的数据集名称合在一起），最后一个之后关闭着色
+    pub fn cycle_dataset(&mut self) {
+        let mut names: Vec<String> = self
+            .state
+            .coverages
+            .values()
+            .flat_map(|coverage| coverage.get_dataset_names())
+            .collect();
+        names.sort();
+        names.dedup();
+        let next = match &self.state.active_dataset {
+            Some(name) => names
+                .iter()
+                .position(|n| n == name)
+                .and_then(|i| names.get(i + 1)),
+            None => names.first(),
+        };
+        self.state.active_dataset = next.cloned();
+        self.state.active_step = 0;
+
+        self.state
+            .layers
+            .retain(|layer| layer.layer_type != LayerType::Dataset);
+        if self.state.active_dataset.is_some() {
+            let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+            coverage_ids.sort();
+            // 填充图层放在最下面，网格线绘制在其上
+            for (i, coverage_id) in coverage_ids.into_iter().enumerate() {
+                let layer = Layer::new_dataset(
+                    coverage_id,
+                    &self.state,
+                    &self.wgpu_ctx.device,
+                    &self.wgpu_ctx.surface_config,
+                );
+                self.state.layers.insert(i, layer);
+            }
+        }
+        self.print_dataset_step();
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 前后切换当前数据集的时间步，以时间步最多的网格为准
+    pub fn step_dataset(&mut self, forward: bool) {
+        let name = match &self.state.active_dataset {
+            Some(name) => name,
+            None => return,
+        };
+        let len = self
+            .state
+            .coverages
+            .values()
+            .filter_map(|coverage| coverage.get_dataset(name))
+            .map(|dataset| dataset.steps.len())
+            .max()
+            .unwrap_or(0);
+        let step = self.state.active_step;
+        self.state.active_step = if forward {
+            (step + 1).min(len.saturating_sub(1))
+        } else {
+            step.saturating_sub(1)
+        };
+        if self.state.active_step != step {
+            self.print_dataset_step();
+            self.state.refresh_layers(&self.wgpu_ctx.device);
+            self.redraw();
+        }
+    }
+    // 输出当前数据集的时间及该时间步和全部时间步的统计
+    fn print_dataset_step(&self) {
+        let name = match &self.state.active_dataset {
+            Some(name) => name,
+            None => return,
+        };
+        let step = self.state.active_step;
+        for coverage in self.state.coverages.values() {
+            if let Some(dataset) = coverage.get_dataset(name) {
+                println!(
+                    "{}: {} step {}/{} time {:?}, {:?}, overall {:?}",
+                    coverage.id,
+                    name,
+                    step + 1,
+                    dataset.steps.len(),
+                    dataset.steps.get(step).map(|s| s.time),
+                    dataset.get_step_statistics(step),
+                    dataset.get_statistics()
+                );
+            }
+        }
+    }
+    // 输出各网格的连通分量，删除孤岛及孤立节点
+    pub fn remove_islands(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let report = coverage.get_components(MAX_ISLAND_FACES);
+            println!("{}: {}", coverage.id, report);
+            let mut removed_nodes = coverage.remove_orphan_nodes().len();
+            for &i in &report.islands {
+                removed_nodes += coverage
+                    .remove_component(&report.components[i].face_ids)
+                    .len();
+            }
+            println!(
+                "{}: {} islands removed, {} nodes removed",
+                coverage.id,
+                report.islands.len(),
+                removed_nodes
+            );
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 将除最大分量外的每个连通分量拆分为单独的网格，id 为 <原 id>_<序号>
+    pub fn split_components(&mut self) {
+        let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+        coverage_ids.sort();
+        for coverage_id in coverage_ids {
+            let coverage = self.state.coverages.get_mut(&coverage_id).unwrap();
+            let report = coverage.get_components(MAX_ISLAND_FACES);
+            let mut parts = Vec::new();
+            for (i, component) in report.components.iter().enumerate().skip(1) {
+                let id = format!("{}_{}", coverage_id, i);
+                parts.push(coverage.extract_component(&component.face_ids, id));
+                coverage.remove_component(&component.face_ids);
+            }
+            println!("{}: split into {} coverages", coverage_id, parts.len() + 1);
+            for part in parts {
+                add_mesh_coverage(&part.id.clone(), part, &mut self.state, &self.wgpu_ctx);
+            }
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 简化所有网格，每次面数减半，相对原始表面的竖向偏差不超过高程范围的百分之一，断裂线经过的面不变
+    pub fn decimate(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let bbox3 = coverage.get_bbox3();
+            let (c_x, c_y) = Service::get_mesh_center(coverage);
+            let breaklines: Vec<Vec<u32>> = self
+                .state
+                .breaklines
+                .iter()
+                .filter(|line| line.len() >= 2)
+                .map(|line| {
+                    let polyline: Vec<(f64, f64)> =
+                        line.iter().map(|p| (p[0] + c_x, p[1] + c_y)).collect();
+                    coverage.get_breakline_nodes(&polyline)
+                })
+                .collect();
+            let options = DecimateOptions {
+                target_faces: Some(coverage.face_map.len() / 2),
+                max_error: Some((bbox3.max_z - bbox3.min_z) * DECIMATE_ERROR_RATIO),
+                breaklines,
+                ..Default::default()
+            };
+            let report = coverage.decimate(&options);
+            println!("{}: {}", coverage.id, report);
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 合并重合节点并删除退化面及狭长小面，随后检查拓扑
+    pub fn cleanup(&mut self) {
+        for coverage in self.state.coverages.values_mut() {
+            let bbox3 = coverage.get_bbox3();
+            let tolerance = (bbox3.max_x - bbox3.min_x).hypot(bbox3.max_y - bbox3.min_y)
+                * CLEANUP_TOLERANCE_RATIO;
+            let report = coverage.cleanup(tolerance, tolerance * tolerance);
+            println!("{}: {}", coverage.id, report);
+            println!("{}: {}", coverage.id, coverage.validate());
+        }
+        self.state.refresh_layers(&self.wgpu_ctx.device);
+        self.redraw();
+    }
+    // 依次切换裁剪方式：保留内部并切开、保留内部按形心、删除内部并切开、删除内部按形心
+    pub fn cycle_clip_options(&mut self) {
+        let options = self.state.clip_options;
+        let (keep, crossing) = match (options.keep, options.crossing) {
+            (ClipKeep::Inside, ClipCrossing::Split) => (ClipKeep::Inside, ClipCrossing::Centroid),
+            (ClipKeep::Inside, ClipCrossing::Centroid) => (ClipKeep::Outside, ClipCrossing::Split),
+            (ClipKeep::Outside, ClipCrossing::Split) => (ClipKeep::Outside, ClipCrossing::Centroid),
+            (ClipKeep::Outside, ClipCrossing::Centroid) => (ClipKeep::Inside, ClipCrossing::Split),
+        };
+        self.state.clip_options = ClipOptions { keep, crossing };
+        println!("clip: keep {:?}, crossing faces {:?}", keep, crossing);
+    }
+    // 以鼠标位置为剖面线增加一个顶点
+    pub fn add_section_point(&mut self) {
+        let local = self
+            .state
+            .scene
+            .screen_to_local(self.state.scene.mouse_position());
+        self.state.section_line.push([local[0], local[1]]);
+        println!(
+            "section point {}: ({:.3}, {:.3})",
+            self.state.section_line.len(),
+            local[0],
+            local[1]
+        );
+    }
+    // 以鼠标位置为当前断裂线增加一个顶点
+    pub fn add_breakline_point(&mut self) {
+        let local = self
+            .state
+            .scene
+            .screen_to_local(self.state.scene.mouse_position());
+        if self.state.breaklines.is_empty() {
+            self.state.breaklines.push(Vec::new());
+        }
+        let count = self.state.breaklines.len();
+        let line = self.state.breaklines.last_mut().unwrap();
+        line.push([local[0], local[1]]);
+        println!(
+            "breakline {} point {}: ({:.3}, {:.3})",
+            count,
+            line.len(),
+            local[0],
+            local[1]
+        );
+    }
+    // 结束当前断裂线，之后的顶点属于新的断裂线；没有正在绘制的断裂线时清空所有断裂线
+    pub fn finish_breakline(&mut self) {
+        match self.state.breaklines.last() {
+            Some(line) if !line.is_empty() => {
+                println!(
+                    "breakline {} finished with {} points",
+                    self.state.breaklines.len(),
+                    line.len()
+                );
+                self.state.breaklines.push(Vec::new());
+            }
+            _ => {
+                self.state.breaklines.clear();
+                println!("breaklines cleared");
+            }
+        }
+    }
+    // 沿剖面线提取各网格的断面并导出到 export 目录，随后清空剖面线
+    pub fn extract_profiles(&mut self) {
+        let section_line = std::mem::take(&mut self.state.section_line);
+        if section_line.len() < 2 {
+            println!("section line needs at least 2 points");
+            return;
+        }
+        let root_path = Path::new("export");
+        for coverage in self.state.coverages.values_mut() {
+            let (c_x, c_y) = Service::get_mesh_center(coverage);
+            let polyline: Vec<(f64, f64)> = section_line
+                .iter()
+                .map(|p| (p[0] + c_x, p[1] + c_y))
+                .collect();
+            let profile = coverage.get_profile(&polyline, self.state.active_step);
+            if profile.points.is_empty() {
+                println!("{}: section line outside mesh", coverage.id);
+                continue;
+            }
+            let (min_z, max_z) = profile
+                .points
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(min, max), p| {
+                    (min.min(p.z), max.max(p.z))
+                });
+            println!(
+                "{}: {} profile points, z {:.3} ~ {:.3}",
+                coverage.id,
+                profile.points.len(),
+                min_z,
+                max_z
+            );
+            match Service::save_profile(root_path, &coverage.id, &profile) {
+                Ok(path_buf) => println!("{}: saved to {:?}", coverage.id, path_buf),
+                Err(e) => eprintln!("{}: profile export failed, {:?}", coverage.id, e),
+            }
+        }
+    }
+    // 显示或关闭等高线，等高距按各网格的高程范围自动选取
+    pub fn toggle_contours(&mut self) {
+        let shown = self
+            .state
+            .layers
+            .iter()
+            .any(|layer| matches!(layer.layer_type, LayerType::Contour(_)));
+        if shown {
+            self.state
+                .layers
+                .retain(|layer| !matches!(layer.layer_type, LayerType::Contour(_)));
+        } else {
+            let mut coverage_ids: Vec<String> = self.state.coverages.keys().cloned().collect();
+            coverage_ids.sort();
+            for coverage_id in coverage_ids {
+                let coverage = self.state.coverages.get_mut(&coverage_id).unwrap();
+                let interval = Service::get_contour_interval(coverage);
+                let layer = Layer::new_contour(
+                    coverage_id,
+                    interval,
+                    &self.state,
+                    &self.wgpu_ctx.device,
+                    &self.wgpu_ctx.surface_config,
+                );
+                self.state.add_layer(layer);
+            }
+            self.state.refresh_layers(&self.wgpu_ctx.device);
+        }
+        self.redraw();
+    }
 }
 
 pub fn drop_file(path_buf: PathBuf, state: &mut State, wgpu_ctx: &WgpuCtx<'_>) {
@@ -120,16 +689,167 @@ pub fn drop_file(path_buf: PathBuf, state: &mut State, wgpu_ctx: &WgpuCtx<'_>) {
                 let mut mesh_coverage = MeshCoverage::new(coverage.id.clone());
                 let dir = path_buf.parent().unwrap();
                 Service::load_mesh(dir, coverage.id.clone(), &mut mesh_coverage);
-                let mut layer = Layer::new(
-                    coverage.id.clone(),
-                    &state,
-                    &wgpu_ctx.device,
-                    &wgpu_ctx.surface_config,
+                add_mesh_coverage(&coverage.name, mesh_coverage, state, wgpu_ctx);
+            }
+        }
+    } else if ext.to_str() == Some("xyz") {
+        // 由散点生成三角网
+        let points = Service::read_xyz_file(&path_buf);
+        let name = path_buf.file_stem().unwrap().to_string_lossy().to_string();
+        let options = TriangulateOptions {
+            min_angle: Some(20.0),
+            ..Default::default()
+        };
+        let (mesh_coverage, report) =
+            MeshCoverage::triangulate(name.clone(), &points, &[], None, &[], &options);
+        println!(
+            "{}: {} points, {} faces, {}",
+            name,
+            points.len(),
+            mesh_coverage.face_map.len(),
+            report
+        );
+        add_mesh_coverage(&name, mesh_coverage, state, wgpu_ctx);
+    } else if ext.to_str() == Some("snap") {
+        // 快照中已有半边拓扑，不需要重新生成
+        match Service::load_snapshot(&path_buf) {
+            Ok(mesh_coverage) => {
+                let name = mesh_coverage.id.clone();
+                add_mesh_coverage(&name, mesh_coverage, state, wgpu_ctx);
+            }
+            Err(e) => eprintln!("{:?}: {:?}", path_buf, e),
+        }
+    } else if ext.to_str() == Some("dat") {
+        // 节点数据集按文件中的顺序对应节点 id 从小到大的顺序，只加到节点数相同的网格上
+        let datasets = Service::read_dat_file(&path_buf);
+        for coverage in state.coverages.values_mut() {
+            let mut node_ids: Vec<u32> = coverage.node_map.keys().copied().collect();
+            node_ids.sort();
+            let ids: HashMap<u32, u32> = node_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (i as u32 + 1, *id))
+                .collect();
+            for dataset in &datasets {
+                let count = dataset.steps.iter().map(|s| s.values.len()).max();
+                if count != Some(node_ids.len()) {
+                    println!(
+                        "{}: {} skipped, {:?} values for {} nodes",
+                        coverage.id,
+                        dataset.name,
+                        count,
+                        node_ids.len()
+                    );
+                    continue;
+                }
+                let mut dataset = dataset.clone();
+                dataset.renumber(&ids);
+                println!(
+                    "{}: {} loaded, {} steps",
+                    coverage.id,
+                    dataset.name,
+                    dataset.steps.len()
+                );
+                coverage.add_dataset(dataset);
+            }
+        }
+        state.refresh_layers(&wgpu_ctx.device);
+    } else if ext.to_str() == Some("gcp") {
+        // 由控制点求变换，只变换包围盒包含原控制点重心的网格，即控制点所在的网格
+        let pairs = Service::read_gcp_file(&path_buf);
+        let affine = match Affine::from_point_pairs(&pairs) {
+            Some(affine) => affine,
+            None => {
+                eprintln!(
+                    "{:?}: {} control points, no transform",
+                    path_buf,
+                    pairs.len()
+                );
+                return;
+            }
+        };
+        let n = pairs.len() as f64;
+        let x = pairs.iter().map(|(p, _)| p.0).sum::<f64>() / n;
+        let y = pairs.iter().map(|(p, _)| p.1).sum::<f64>() / n;
+        let residual = (pairs
+            .iter()
+            .map(|(p, q)| {
+                let (u, v) = affine.apply(p.0, p.1);
+                (u - q.0).powi(2) + (v - q.1).powi(2)
+            })
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        println!("{:?}: {:?}, rms residual {:.4}", path_buf, affine, residual);
+        for coverage in state.coverages.values_mut() {
+            let bbox3 = coverage.get_bbox3();
+            if x < bbox3.min_x || x > bbox3.max_x || y < bbox3.min_y || y > bbox3.max_y {
+                continue;
+            }
+            coverage.transform(&affine);
+            let bbox3 = coverage.get_bbox3();
+            println!(
+                "{}: transformed, x {:.3} ~ {:.3}, y {:.3} ~ {:.3}",
+                coverage.id, bbox3.min_x, bbox3.max_x, bbox3.min_y, bbox3.max_y
+            );
+        }
+        state.refresh_layers(&wgpu_ctx.device);
+    } else if ext.to_str() == Some("shp") {
+        // 用面要素裁剪所有网格，裁剪方式由 F 键切换
+        let polygons = Service::read_shp_polygons(&path_buf);
+        let options = state.clip_options;
+        println!(
+            "{:?}: keep {:?}, crossing faces {:?}",
+            path_buf, options.keep, options.crossing
+        );
+        for coverage in state.coverages.values_mut() {
+            let report = coverage.clip(&polygons, &options);
+            println!(
+                "{}: {} faces removed, {} faces split into {}, {} nodes removed",
+                coverage.id,
+                report.removed_faces.len(),
+                report.split_faces.len(),
+                report.created_faces.len(),
+                report.removed_nodes.len()
+            );
+            if !report.skipped_faces.is_empty() {
+                println!(
+                    "{}: faces with missing nodes skipped: {:?}",
+                    coverage.id, report.skipped_faces
                 );
-                Service::set_mesh_data(&wgpu_ctx.device, &mut mesh_coverage, &mut layer);
-                state.add_coverage(mesh_coverage);
-                state.add_layer(layer);
             }
         }
+        state.refresh_layers(&wgpu_ctx.device);
     }
 }
+
+fn add_mesh_coverage(
+    name: &str,
+    mut mesh_coverage: MeshCoverage,
+    state: &mut State,
+    wgpu_ctx: &WgpuCtx<'_>,
+) {
+    // 检查网格拓扑
+    let report = mesh_coverage.validate();
+    println!("{}: {}", name, report);
+    let mut layer = Layer::new(
+        mesh_coverage.id.clone(),
+        state,
+        &wgpu_ctx.device,
+        &wgpu_ctx.surface_config,
+    );
+    Service::set_mesh_data(&wgpu_ctx.device, &mut mesh_coverage, &mut layer);
+    // 外边界及孔洞
+    let mut boundary_layer = Layer::new_with_color(
+        mesh_coverage.id.clone(),
+        [1.0, 0.0, 0.0, 1.0],
+        state,
+        &wgpu_ctx.device,
+        &wgpu_ctx.surface_config,
+    );
+    boundary_layer.layer_type = LayerType::Boundary;
+    Service::set_boundary_data(&wgpu_ctx.device, &mut mesh_coverage, &mut boundary_layer);
+    state.add_coverage(mesh_coverage);
+    state.add_layer(layer);
+    state.add_layer(boundary_layer);
+}